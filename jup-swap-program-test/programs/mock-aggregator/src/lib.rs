@@ -0,0 +1,91 @@
+//! Stand-in for the Jupiter program, loaded at Jupiter's program id in the LiteSVM tests. It
+//! swaps at whatever rate the instruction asks for against a pool owned by its own PDA.
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub const POOL_AUTHORITY_SEED: &[u8] = b"pool";
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Instruction data is `discriminator: [u8; 8], amount_in: u64 LE, amount_out: u64 LE`, the
+/// discriminator being whatever the caller's allowlist expects, accounts are:
+///
+/// 0. `[signer]` user
+/// 1. `[writable]` user source token account
+/// 2. `[writable]` user destination token account
+/// 3. `[writable]` pool input token account
+/// 4. `[writable]` pool output token account
+/// 5. `[]` pool authority, the PDA of [`POOL_AUTHORITY_SEED`]
+/// 6. `[]` token program
+///
+/// Anything after that is ignored.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    if data.len() != 24 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (amount_in, amount_out) = data[8..].split_at(8);
+    let amount_in = u64::from_le_bytes(amount_in.try_into().unwrap());
+    let amount_out = u64::from_le_bytes(amount_out.try_into().unwrap());
+
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let user_source = next_account_info(accounts_iter)?;
+    let user_destination = next_account_info(accounts_iter)?;
+    let pool_input = next_account_info(accounts_iter)?;
+    let pool_output = next_account_info(accounts_iter)?;
+    let pool_authority = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    let (pool_authority_key, bump) =
+        Pubkey::find_program_address(&[POOL_AUTHORITY_SEED], program_id);
+    if *pool_authority.key != pool_authority_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            user_source.key,
+            pool_input.key,
+            user.key,
+            &[],
+            amount_in,
+        )?,
+        &[
+            user_source.clone(),
+            pool_input.clone(),
+            user.clone(),
+            token_program.clone(),
+        ],
+    )?;
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            pool_output.key,
+            user_destination.key,
+            pool_authority.key,
+            &[],
+            amount_out,
+        )?,
+        &[
+            pool_output.clone(),
+            user_destination.clone(),
+            pool_authority.clone(),
+            token_program.clone(),
+        ],
+        &[&[POOL_AUTHORITY_SEED, &[bump]]],
+    )?;
+
+    Ok(())
+}
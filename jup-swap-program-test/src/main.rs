@@ -1,6 +1,8 @@
 mod helpers;
 mod retryable_rpc;
 mod debug;
+mod mint_risks;
+mod vault_accounts;
 
 use tokio;
 use tokio::sync::RwLock;
@@ -57,6 +59,8 @@ use jup_swap::{
 };
 use crate::helpers::{get_account_fields, get_discriminator,get_address_lookup_table_accounts,get_address_lookup_table_accounts_with_retry};
 use crate::debug::{serialize_and_encode, decode_and_deserialize,PACKET_DATA_SIZE};
+use crate::vault_accounts::print_closable_vault_accounts;
+use crate::mint_risks::warn_about_mint_risks;
 const INPUT_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
 const INPUT_AMOUNT: u64 = 2_000_000;
 const OUTPUT_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
@@ -64,16 +68,35 @@ const OUTPUT_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1
 const CPI_SWAP_PROGRAM_ID: Pubkey = pubkey!("LMMGrBSX84ZC519PSBkppyVdT4XfM3VP3hw4XLXqhrf");
 const JUPITER_V6_AGG_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 
 struct LatestBlockhash {
     blockhash: RwLock<Hash>,
     slot: AtomicU64,
 }
 
+#[derive(BorshSerialize, BorshDeserialize)]
+struct SwapCallback {
+    pub data: Vec<u8>,
+    pub accounts_len: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct VestingSchedule {
+    pub id: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 struct SwapIxData {
     pub data: Vec<u8>,
     pub amount: u64,
+    pub callback: Option<SwapCallback>,
+    pub receipt_id: Option<u64>,
+    pub vesting_schedule: Option<VestingSchedule>,
+    pub memo: Option<String>,
 }
 
 #[tokio::main]
@@ -87,6 +110,17 @@ async fn main() {
         CommitmentConfig::confirmed(),
     ));
 
+    //`cargo run -- closable-vault-accounts [INTEGRATOR_ID]` only lists the empty token accounts of
+    //the shared vault, or of the integrator's vault
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("closable-vault-accounts") {
+        let integrator_id = args
+            .get(2)
+            .map(|id| id.parse().expect("invalid integrator id"));
+        print_closable_vault_accounts(rpc_url, &CPI_SWAP_PROGRAM_ID, integrator_id).await;
+        return;
+    }
+
     let rpc_client_clone = rpc_client.clone();
     
     let latest_blockhash = Arc::new(LatestBlockhash {
@@ -113,6 +147,8 @@ async fn main() {
 
     let jupiter_swap_api_client = JupiterSwapApiClient::new(api_base_url);
 
+    warn_about_mint_risks(rpc_url, &OUTPUT_MINT).await;
+
     println!("Fetching quote...");
     let quote_request = QuoteRequest {
         amount: INPUT_AMOUNT,
@@ -142,6 +178,19 @@ async fn main() {
     println!("Payer Address: {}", payer_address);
 
     let (vault, _) = Pubkey::find_program_address(&[b"vault"], &CPI_SWAP_PROGRAM_ID);
+    let (config, _) = Pubkey::find_program_address(&[b"config"], &CPI_SWAP_PROGRAM_ID);
+    let (mint_rate_limit, _) = Pubkey::find_program_address(
+        &[b"mint_rate_limit", vault.as_ref(), OUTPUT_MINT.as_ref()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (caller_rate_limit, _) = Pubkey::find_program_address(
+        &[b"caller_rate_limit", vault.as_ref(), payer_address.as_ref(), OUTPUT_MINT.as_ref()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
+    let (mint_stats, _) = Pubkey::find_program_address(
+        &[b"mint_stats", INPUT_MINT.as_ref(), OUTPUT_MINT.as_ref()],
+        &CPI_SWAP_PROGRAM_ID,
+    );
     
     svm.airdrop(&vault, 1_000_000_000).unwrap(); 
 
@@ -189,6 +238,7 @@ async fn main() {
     let input_token_account = get_associated_token_address(&vault, &INPUT_MINT);
     let output_token_account = get_associated_token_address(&vault, &OUTPUT_MINT);
     let recipient_token_account = get_associated_token_address(&recipient_address, &OUTPUT_MINT);
+    let payer_output_token_account = get_associated_token_address(&payer_address, &OUTPUT_MINT);
 
     println!("Input Token Account: {}", input_token_account);
     println!("Output Token Account: {}", output_token_account);
@@ -207,12 +257,22 @@ async fn main() {
         &OUTPUT_MINT,
         &TOKEN_PROGRAM_ID,
     );
+    let create_payer_output_ata_ix = create_associated_token_account_idempotent(
+        &payer.pubkey(),
+        &payer_address,
+        &OUTPUT_MINT,
+        &TOKEN_PROGRAM_ID,
+    );
 
     println!("Swap Instruction Data: {:?}", response.swap_instruction.data);
 
     let instruction_data = SwapIxData {
         data: response.swap_instruction.data,
-        amount: 1000 // any amount tbh
+        amount: 1000, // any amount tbh
+        callback: None,
+        receipt_id: None,
+        vesting_schedule: None,
+        memo: None,
     };
 
     let mut serialized_data = Vec::from(get_discriminator("global:swap"));
@@ -220,6 +280,7 @@ async fn main() {
 
     println!("Serialized Swap Instruction Data: {:?}", serialized_data);
     let mut accounts = vec![
+        AccountMeta::new(payer_address, true),        // payer
         AccountMeta::new_readonly(INPUT_MINT, false), // input mint
         AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false), // input mint program (for now, just hardcoded to SPL and not SPL 2022)
         AccountMeta::new_readonly(OUTPUT_MINT, false),      // output mint
@@ -231,6 +292,24 @@ async fn main() {
         AccountMeta::new_readonly(recipient_address, false),                  // recipient 
         AccountMeta::new_readonly(ATA_ID, false),                       // ATA program
         AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false), // jupiter program
+        AccountMeta::new_readonly(config, false),                    // config
+        AccountMeta::new(mint_rate_limit, false),                    // output mint rate limit
+        AccountMeta::new(caller_rate_limit, false),                  // payer rate limit
+        AccountMeta::new(mint_stats, false),                         // mint pair stats
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // callback program (none, optional accounts are passed as the program id)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // receipt (none)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // vesting (none)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // vesting escrow (none)
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),         // system program
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // input mint pyth price update (none)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // output mint pyth price update (none)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // integrator (none, swaps out of the shared vault)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // integrator fee treasury token account (none)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // referrer (none)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // referrer account (none)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // referrer escrow (none)
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),       // memo program (none)
+        AccountMeta::new(payer_output_token_account, false),         // surplus destination (the payer's output ATA)
     ];
     // //Add the addtional accounts from the response 
     let remaining_accounts = response.swap_instruction.accounts;
@@ -263,6 +342,7 @@ async fn main() {
             cup_ix.clone(),
             create_output_ata_ix.clone(),
             create_recipient_ata_ix.clone(),
+            create_payer_output_ata_ix.clone(),
             swap_ix.clone(),
         ],
         &address_lookup_table_accounts,
@@ -288,7 +368,14 @@ async fn main() {
     println!("Latest blockhash: {}", recent_blockhash);
     let message = Message::try_compile(
         &payer_address,
-        &[cu_ix, cup_ix, create_output_ata_ix, create_recipient_ata_ix, swap_ix],
+        &[
+            cu_ix,
+            cup_ix,
+            create_output_ata_ix,
+            create_recipient_ata_ix,
+            create_payer_output_ata_ix,
+            swap_ix,
+        ],
         &address_lookup_table_accounts,
         *recent_blockhash,
     )
@@ -0,0 +1,96 @@
+use {
+    solana_pubkey::{pubkey, Pubkey},
+    spl_token::state::Mint,
+    solana_program_pack::Pack,
+};
+use crate::retryable_rpc::RetryableRpcClient;
+
+const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+// Token-2022 pads mints to the token account size, then stores the account type and the
+// extensions as [type: u16 LE, length: u16 LE, value] entries
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const EXTENSION_DEFAULT_ACCOUNT_STATE: u16 = 6;
+const EXTENSION_NON_TRANSFERABLE: u16 = 9;
+const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+const ACCOUNT_STATE_FROZEN: u8 = 2;
+
+/// Same flags as the program's `mint_risks` module, the ones `swap` blocks by default are all but
+/// `FreezeAuthority`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MintRisk {
+    PermanentDelegate,
+    NonTransferable,
+    DefaultFrozen,
+    FreezeAuthority,
+}
+
+impl MintRisk {
+    pub fn description(&self) -> &'static str {
+        match self {
+            MintRisk::PermanentDelegate => "a permanent delegate can move or burn the payout",
+            MintRisk::NonTransferable => "the payout can never be transferred again",
+            MintRisk::DefaultFrozen => "new token accounts start out frozen",
+            MintRisk::FreezeAuthority => "the freeze authority can freeze the recipient's account",
+        }
+    }
+}
+
+/// Parses a Token or Token-2022 mint account the way the program does before paying out in it
+pub fn mint_risks(owner: &Pubkey, data: &[u8]) -> anyhow::Result<Vec<MintRisk>> {
+    let mint = Mint::unpack(data.get(..Mint::LEN).ok_or(anyhow::anyhow!("not a mint"))?)?;
+    let mut risks = Vec::new();
+    if mint.freeze_authority.is_some() {
+        risks.push(MintRisk::FreezeAuthority);
+    }
+    if *owner != TOKEN_2022_PROGRAM_ID || data.len() <= ACCOUNT_TYPE_OFFSET {
+        return Ok(risks);
+    }
+
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let extension = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = data
+            .get(offset + 4..offset + 4 + len)
+            .ok_or(anyhow::anyhow!("truncated mint extension"))?;
+        match extension {
+            // Uninitialized, the rest is padding
+            0 => break,
+            EXTENSION_PERMANENT_DELEGATE if value.iter().any(|byte| *byte != 0) => {
+                risks.push(MintRisk::PermanentDelegate)
+            }
+            EXTENSION_NON_TRANSFERABLE => risks.push(MintRisk::NonTransferable),
+            EXTENSION_DEFAULT_ACCOUNT_STATE if value.first() == Some(&ACCOUNT_STATE_FROZEN) => {
+                risks.push(MintRisk::DefaultFrozen)
+            }
+            _ => {}
+        }
+        offset += 4 + len;
+    }
+    Ok(risks)
+}
+
+/// Pre-flight check of the output mint, warns about anything that could trap the payout or get
+/// the swap rejected
+pub async fn warn_about_mint_risks(rpc_url: &str, mint: &Pubkey) {
+    let client = RetryableRpcClient::new(rpc_url);
+    let account = match client.get_multiple_accounts_with_retry(&[*mint]).await {
+        Ok(accounts) => accounts.into_iter().next().flatten(),
+        Err(e) => {
+            println!("failed to fetch output mint {}: {e:#?}", mint);
+            return;
+        }
+    };
+    let Some(account) = account else {
+        println!("Warning: output mint {} doesn't exist", mint);
+        return;
+    };
+    match mint_risks(&account.owner, &account.data) {
+        Ok(risks) => {
+            for risk in risks {
+                println!("Warning: output mint {}: {}", mint, risk.description());
+            }
+        }
+        Err(e) => println!("failed to parse output mint {}: {e:#?}", mint),
+    }
+}
@@ -1,10 +1,12 @@
 use std::time::Duration;
 
 use anyhow::bail;
+use serde_json::json;
 use solana_client::{
     client_error::ClientError, nonblocking::rpc_client::RpcClient,
     rpc_client::SerializableTransaction, rpc_config::RpcSendTransactionConfig,
-    rpc_request::RpcError,
+    rpc_request::{RpcError, RpcRequest},
+    rpc_response::{Response, RpcKeyedAccount},
 };
 
 use {
@@ -65,6 +67,54 @@ impl RetryableRpcClient {
         }
     }
 
+    /// Token accounts of `owner` under `token_program`, base64 encoded so Token-2022 accounts come
+    /// back with their extensions instead of parsed
+    #[allow(unused)]
+    pub async fn get_token_accounts_by_owner_with_retry(
+        &self,
+        owner: &Pubkey,
+        token_program: &Pubkey,
+    ) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+        let params = json!([
+            owner.to_string(),
+            { "programId": token_program.to_string() },
+            { "encoding": "base64" },
+        ]);
+        let mut retry_count = 0;
+
+        loop {
+            if retry_count > MAX_RETRY_COUNT {
+                bail!(anyhow::anyhow!("Max retry count reached"));
+            }
+
+            match self
+                .rpc_client
+                .send::<Response<Vec<RpcKeyedAccount>>>(
+                    RpcRequest::GetTokenAccountsByOwner,
+                    params.clone(),
+                )
+                .await
+            {
+                Ok(response) => {
+                    return response
+                        .value
+                        .into_iter()
+                        .map(|keyed| {
+                            let account = keyed.account.decode::<Account>().ok_or_else(|| {
+                                anyhow::anyhow!("could not decode account {}", keyed.pubkey)
+                            })?;
+                            Ok((keyed.pubkey.parse()?, account))
+                        })
+                        .collect()
+                }
+                Err(_err) => {
+                    retry_count += 1;
+                    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+                }
+            }
+        }
+    }
+
     /// Extracted from RpcClient but changed to reasonable commitment
     pub async fn send_and_confirm_transaction(
         &self,
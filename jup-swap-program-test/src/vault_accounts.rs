@@ -0,0 +1,97 @@
+use anyhow;
+use {
+    solana_program_pack::Pack,
+    solana_pubkey::{pubkey, Pubkey},
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    spl_token::state::Account as TokenAccount,
+};
+use crate::retryable_rpc::RetryableRpcClient;
+
+const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// A vault ATA holding no tokens, which `close_vault_token_account` can close to reclaim its rent
+#[derive(Debug)]
+pub struct ClosableVaultAccount {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub lamports: u64,
+}
+
+/// The shared vault, or the vault of the integrator with id `integrator_id`
+pub fn vault_address(program_id: &Pubkey, integrator_id: Option<u64>) -> Pubkey {
+    match integrator_id {
+        Some(id) => {
+            let (integrator, _) =
+                Pubkey::find_program_address(&[b"integrator", &id.to_le_bytes()], program_id);
+            Pubkey::find_program_address(&[b"vault", integrator.as_ref()], program_id).0
+        }
+        None => Pubkey::find_program_address(&[b"vault"], program_id).0,
+    }
+}
+
+/// Finds the vault's empty token accounts among everything it owns under SPL Token and
+/// Token-2022
+pub async fn get_closable_vault_accounts(
+    client: &RetryableRpcClient,
+    vault: &Pubkey,
+) -> anyhow::Result<Vec<ClosableVaultAccount>> {
+    let mut closable = Vec::new();
+    for token_program in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+        let accounts = client
+            .get_token_accounts_by_owner_with_retry(vault, &token_program)
+            .await?;
+
+        for (address, account) in accounts {
+            if account.data.len() < TokenAccount::LEN {
+                continue;
+            }
+            // Token-2022 accounts carry extensions after the base layout, which is all we need
+            let Ok(token_account) = TokenAccount::unpack(&account.data[..TokenAccount::LEN]) else {
+                continue;
+            };
+            // The instruction only closes the vault's ATAs
+            let ata = get_associated_token_address_with_program_id(
+                vault,
+                &token_account.mint,
+                &token_program,
+            );
+            if token_account.amount == 0 && address == ata {
+                closable.push(ClosableVaultAccount {
+                    address,
+                    mint: token_account.mint,
+                    token_program,
+                    lamports: account.lamports,
+                })
+            }
+        }
+    }
+    Ok(closable)
+}
+
+/// `cargo run -- closable-vault-accounts [INTEGRATOR_ID]`
+pub async fn print_closable_vault_accounts(
+    rpc_url: &str,
+    program_id: &Pubkey,
+    integrator_id: Option<u64>,
+) {
+    let vault = vault_address(program_id, integrator_id);
+    let client = RetryableRpcClient::new(rpc_url);
+
+    println!("Looking for empty token accounts of vault {}...", vault);
+    match get_closable_vault_accounts(&client, &vault).await {
+        Ok(accounts) if accounts.is_empty() => println!("No closable vault token accounts"),
+        Ok(accounts) => {
+            let total: u64 = accounts.iter().map(|account| account.lamports).sum();
+            for account in &accounts {
+                println!(
+                    "{} mint: {} token program: {} rent: {} lamports",
+                    account.address, account.mint, account.token_program, account.lamports
+                );
+            }
+            println!("{} closable accounts, {} lamports reclaimable", accounts.len(), total);
+        }
+        Err(e) => println!("failed to fetch vault token accounts: {e:#?}"),
+    }
+}
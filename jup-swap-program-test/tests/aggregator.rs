@@ -0,0 +1,75 @@
+//! The config's aggregator allowlist: which programs routes may call and with which
+//! instructions.
+
+mod common;
+
+use {
+    common::{
+        assert_error, Aggregator, ConfigChange, SwapFixture, TestEnv, JUPITER_V6_AGG_PROGRAM_ID,
+        ROUTE_DISCRIMINATOR, SWAP_CALLER_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 2_000_000;
+const OTHER_DISCRIMINATOR: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+fn allow_aggregators(env: &mut TestEnv, aggregators: Vec<Aggregator>) {
+    env.apply_change(ConfigChange::Aggregators { aggregators });
+}
+
+/// The fixture's swap with the route's instruction discriminator replaced by `discriminator`.
+fn swap_with_discriminator(
+    env: &mut TestEnv,
+    fixture: &mut SwapFixture,
+    discriminator: [u8; 8],
+) -> TransactionResult {
+    fixture.route.data[..8].copy_from_slice(&discriminator);
+    env.send(&[fixture.instruction(AMOUNT_OUT)], &[&fixture.payer])
+}
+
+#[test]
+fn rejects_aggregator_off_allowlist() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    fixture.accounts.aggregator_program = SWAP_CALLER_PROGRAM_ID;
+    let result = env.send(&[fixture.instruction(AMOUNT_OUT)], &[&fixture.payer]);
+    assert_error(result, "AggregatorNotAllowed");
+}
+
+#[test]
+fn rejects_instruction_off_aggregator_allowlist() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let result = swap_with_discriminator(&mut env, &mut fixture, OTHER_DISCRIMINATOR);
+    assert_error(result, "RouteInstructionNotAllowed");
+}
+
+#[test]
+fn allows_newly_listed_instruction() {
+    let mut env = TestEnv::new();
+    allow_aggregators(
+        &mut env,
+        vec![Aggregator {
+            program_id: JUPITER_V6_AGG_PROGRAM_ID,
+            discriminators: vec![OTHER_DISCRIMINATOR],
+        }],
+    );
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    swap_with_discriminator(&mut env, &mut fixture, OTHER_DISCRIMINATOR).expect("swap failed");
+
+    //The old instruction is no longer listed
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let result = swap_with_discriminator(&mut env, &mut fixture, ROUTE_DISCRIMINATOR);
+    assert_error(result, "RouteInstructionNotAllowed");
+}
+
+#[test]
+fn rejects_aggregator_removed_from_allowlist() {
+    let mut env = TestEnv::new();
+    allow_aggregators(&mut env, vec![]);
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let result = env.send(&[fixture.instruction(AMOUNT_OUT)], &[&fixture.payer]);
+    assert_error(result, "AggregatorNotAllowed");
+}
@@ -0,0 +1,102 @@
+//! `swap` calling back into an allowlisted program after the payout. The mock aggregator stands
+//! in for the callback program: called with no input, it pays the amount appended to its data
+//! into a deposit account, so the deposit shows what the callback was told.
+
+mod common;
+
+use {
+    common::{
+        assert_error, swap_instruction, ConfigChange, SwapArgs, SwapCallback, SwapFixture, TestEnv,
+        JUPITER_V6_AGG_PROGRAM_ID,
+    },
+    solana_instruction::Instruction,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 2_000_000;
+
+/// A swap with a callback depositing the paid amount, and the deposit account.
+fn swap_with_callback(
+    env: &mut TestEnv,
+    fixture: &SwapFixture,
+    accounts_len: u8,
+) -> (Instruction, Pubkey) {
+    let payer = fixture.payer.pubkey();
+    let callback_input_mint = env.mint();
+    let source = env.token_account(&payer, &callback_input_mint, 0);
+    let deposit = Pubkey::new_unique();
+    env.set_token_account(deposit, fixture.accounts.output_mint, payer, 0);
+    //The pool pays out whatever amount the callback gets, so fund it with the payout
+    let callback_route = env.route(
+        payer,
+        source,
+        deposit,
+        callback_input_mint,
+        fixture.accounts.output_mint,
+        0,
+        AMOUNT_OUT,
+    );
+
+    let mut accounts = fixture.accounts.clone();
+    accounts.callback_program = Some(JUPITER_V6_AGG_PROGRAM_ID);
+    let mut args = SwapArgs::new(&fixture.route, AMOUNT_OUT);
+    args.callback = Some(SwapCallback {
+        //Discriminator and a zero input, the paid amount gets appended after them
+        data: callback_route.data[..16].to_vec(),
+        accounts_len,
+    });
+    let ix = swap_instruction(&accounts, args, &fixture.route, &callback_route.accounts);
+    (ix, deposit)
+}
+
+fn allow_callback(env: &mut TestEnv) {
+    env.apply_change(ConfigChange::CallbackPrograms {
+        programs: vec![JUPITER_V6_AGG_PROGRAM_ID],
+    });
+}
+
+#[test]
+fn calls_allowlisted_program_with_paid_amount() {
+    let mut env = TestEnv::new();
+    allow_callback(&mut env);
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let (ix, deposit) = swap_with_callback(&mut env, &fixture, 8);
+    env.send(&[ix], &[&fixture.payer]).expect("swap failed");
+
+    let recipient_token_account = fixture.accounts.recipient_token_account.unwrap();
+    assert_eq!(env.balance(&recipient_token_account), AMOUNT_OUT);
+    assert_eq!(env.balance(&deposit), AMOUNT_OUT);
+}
+
+#[test]
+fn rejects_callback_program_off_allowlist() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let (ix, _) = swap_with_callback(&mut env, &fixture, 8);
+    let result = env.send(&[ix], &[&fixture.payer]);
+    assert_error(result, "CallbackProgramNotAllowed");
+}
+
+#[test]
+fn rejects_callback_without_callback_program() {
+    let mut env = TestEnv::new();
+    allow_callback(&mut env);
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let (mut ix, _) = swap_with_callback(&mut env, &fixture, 8);
+    //callback_program
+    ix.accounts[16] = common::none();
+    let result = env.send(&[ix], &[&fixture.payer]);
+    assert_error(result, "MissingCallbackProgram");
+}
+
+#[test]
+fn rejects_more_callback_accounts_than_passed() {
+    let mut env = TestEnv::new();
+    allow_callback(&mut env);
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let (ix, _) = swap_with_callback(&mut env, &fixture, u8::MAX);
+    let result = env.send(&[ix], &[&fixture.payer]);
+    assert_error(result, "InvalidCallbackAccounts");
+}
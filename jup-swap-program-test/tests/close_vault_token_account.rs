@@ -0,0 +1,70 @@
+//! `close_vault_token_account`: reclaiming the rent of empty vault token accounts.
+
+mod common;
+
+use {
+    common::{assert_error, ata, instruction, none, shared_vault, TestEnv, TOKEN_PROGRAM_ID},
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+fn close_vault_token_account(
+    env: &mut TestEnv,
+    authority: &Keypair,
+    mint: Pubkey,
+    rent_receiver: Pubkey,
+) -> TransactionResult {
+    let ix = instruction(
+        "close_vault_token_account",
+        (),
+        vec![
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new_readonly(env.config, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(shared_vault(), false),
+            AccountMeta::new(ata(&shared_vault(), &mint), false),
+            AccountMeta::new(rent_receiver, false),
+            none(),
+        ],
+    );
+    env.send(&[ix], &[authority])
+}
+
+#[test]
+fn closes_empty_account_to_rent_receiver() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    let mint = env.mint();
+    let vault_token_account = env.token_account(&shared_vault(), &mint, 0);
+    let rent = env.svm.get_balance(&vault_token_account).unwrap();
+    let rent_receiver = Pubkey::new_unique();
+
+    close_vault_token_account(&mut env, &authority, mint, rent_receiver)
+        .expect("close_vault_token_account failed");
+    assert!(!env.exists(&vault_token_account));
+    assert_eq!(env.svm.get_balance(&rent_receiver), Some(rent));
+}
+
+#[test]
+fn rejects_account_holding_tokens() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    let mint = env.mint();
+    env.token_account(&shared_vault(), &mint, 1);
+    let result = close_vault_token_account(&mut env, &authority, mint, authority.pubkey());
+    assert_error(result, "NonZeroBalance");
+}
+
+#[test]
+fn rejects_others_closing_vault_accounts() {
+    let mut env = TestEnv::new();
+    let stranger = env.wallet();
+    let mint = env.mint();
+    env.token_account(&shared_vault(), &mint, 0);
+    let result = close_vault_token_account(&mut env, &stranger, mint, stranger.pubkey());
+    assert_error(result, "Unauthorized");
+}
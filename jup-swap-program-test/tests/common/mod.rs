@@ -0,0 +1,886 @@
+//! LiteSVM setup shared by the program tests: the programs deployed the way they are on chain,
+//! the config created through `initialize_config`, token accounts, mock aggregator routes and
+//! the accounts of a plain `swap`. Build the programs first:
+//!
+//! anchor build (in ../jup-swap-program)
+//! cargo build-sbf --manifest-path programs/mock-aggregator/Cargo.toml --sbf-out-dir target/deploy
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use sha2::{Digest, Sha256};
+use {
+    litesvm::{types::TransactionResult, LiteSVM},
+    solana_account::Account,
+    solana_clock::Clock,
+    solana_compute_budget_interface::ComputeBudgetInstruction,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_message::{Message, VersionedMessage},
+    solana_program_option::COption,
+    solana_program_pack::Pack,
+    solana_pubkey::{pubkey, Pubkey},
+    solana_signer::Signer,
+    solana_transaction::versioned::VersionedTransaction,
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    spl_token::state::{Account as TokenAccount, AccountState, Mint},
+};
+
+pub const CPI_SWAP_PROGRAM_ID: Pubkey = pubkey!("LMMGrBSX84ZC519PSBkppyVdT4XfM3VP3hw4XLXqhrf");
+pub const SWAP_CALLER_PROGRAM_ID: Pubkey = pubkey!("SuCvJuapuxYGUbbYsdeLCaQkAm6xSmHXuYTqrMYD5SL");
+pub const JUPITER_V6_AGG_PROGRAM_ID: Pubkey =
+    pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ATA_PROGRAM_ID: Pubkey = spl_associated_token_account::ID;
+pub const INSTRUCTIONS_SYSVAR_ID: Pubkey = pubkey!("Sysvar1nstructions1111111111111111111111111");
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+const CPI_SWAP_PROGRAM_PATH: &str = "../jup-swap-program/target/deploy/jup_swap_program.so";
+const SWAP_CALLER_PROGRAM_PATH: &str = "../jup-swap-program/target/deploy/swap_caller.so";
+const MOCK_AGGREGATOR_PROGRAM_PATH: &str = "target/deploy/mock_aggregator.so";
+
+// Jupiter's `route`, one of the instructions the default config allows
+pub const ROUTE_DISCRIMINATOR: [u8; 8] = [229, 23, 203, 151, 122, 227, 173, 42];
+// Seconds a config change stays queued on a fresh config
+pub const DEFAULT_TIMELOCK_DELAY: i64 = 2 * 24 * 60 * 60;
+
+pub fn discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&Sha256::digest(name.as_bytes())[..8]);
+    discriminator
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &CPI_SWAP_PROGRAM_ID).0
+}
+
+/// An instruction of `jup_swap_program`, `args` serialized after its discriminator.
+pub fn instruction(
+    name: &str,
+    args: impl BorshSerialize,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut data = discriminator(&format!("global:{name}")).to_vec();
+    args.serialize(&mut data).unwrap();
+    Instruction {
+        program_id: CPI_SWAP_PROGRAM_ID,
+        accounts,
+        data,
+    }
+}
+
+/// An optional account left out, which Anchor expects as the program's own id.
+pub fn none() -> AccountMeta {
+    AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false)
+}
+
+pub fn ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &TOKEN_PROGRAM_ID)
+}
+
+pub fn config_address() -> Pubkey {
+    pda(&[b"config"])
+}
+
+pub fn shared_vault() -> Pubkey {
+    pda(&[b"vault"])
+}
+
+pub fn mint_rate_limit_address(vault: &Pubkey, mint: &Pubkey) -> Pubkey {
+    pda(&[b"mint_rate_limit", vault.as_ref(), mint.as_ref()])
+}
+
+pub fn caller_rate_limit_address(vault: &Pubkey, caller: &Pubkey, mint: &Pubkey) -> Pubkey {
+    pda(&[
+        b"caller_rate_limit",
+        vault.as_ref(),
+        caller.as_ref(),
+        mint.as_ref(),
+    ])
+}
+
+pub fn mint_stats_address(input_mint: &Pubkey, output_mint: &Pubkey) -> Pubkey {
+    pda(&[b"mint_stats", input_mint.as_ref(), output_mint.as_ref()])
+}
+
+pub fn pending_change_address(proposer: &Pubkey, id: u64) -> Pubkey {
+    pda(&[b"pending_change", proposer.as_ref(), &id.to_le_bytes()])
+}
+
+pub fn integrator_address(id: u64) -> Pubkey {
+    pda(&[b"integrator", &id.to_le_bytes()])
+}
+
+pub fn integrator_vault(integrator: &Pubkey) -> Pubkey {
+    pda(&[b"vault", integrator.as_ref()])
+}
+
+/// Fails unless the transaction failed with the program's `error`, by its name in `SwapError`.
+pub fn assert_error(result: TransactionResult, error: &str) {
+    let failure = result.expect_err(&format!("expected {error}"));
+    let expected = format!("Error Code: {error}.");
+    assert!(
+        failure.meta.logs.iter().any(|log| log.contains(&expected)),
+        "expected {error}, got {:?}\n{:#?}",
+        failure.err,
+        failure.meta.logs
+    );
+}
+
+// Mirrors of the program's config types, borsh encoded the same way
+
+#[derive(BorshSerialize, Clone)]
+pub struct OracleFeed {
+    pub mint: Pubkey,
+    pub feed_id: [u8; 32],
+}
+
+#[derive(BorshSerialize, Clone)]
+pub struct Aggregator {
+    pub program_id: Pubkey,
+    pub discriminators: Vec<[u8; 8]>,
+}
+
+#[derive(BorshSerialize, Clone)]
+pub enum ConfigChange {
+    Authority {
+        authority: Pubkey,
+    },
+    Guardian {
+        guardian: Pubkey,
+    },
+    TimelockDelay {
+        delay: i64,
+    },
+    Aggregators {
+        aggregators: Vec<Aggregator>,
+    },
+    CallbackPrograms {
+        programs: Vec<Pubkey>,
+    },
+    OracleConfig {
+        max_deviation_bps: u16,
+        max_age: i64,
+        feeds: Vec<OracleFeed>,
+    },
+    MintRisks {
+        blocked_mint_risks: u8,
+        trusted_mints: Vec<Pubkey>,
+    },
+    SurplusTreasury {
+        surplus_treasury: Option<Pubkey>,
+    },
+    RateLimits {
+        window_slots: u64,
+        default_mint_cap: u64,
+        default_caller_cap: u64,
+    },
+    MintRateLimit {
+        vault: Pubkey,
+        mint: Pubkey,
+        cap: Option<u64>,
+    },
+    CallerRateLimit {
+        vault: Pubkey,
+        caller: Pubkey,
+        mint: Pubkey,
+        cap: Option<u64>,
+    },
+    NewIntegrator {
+        id: u64,
+        authority: Pubkey,
+        fee_bps: u16,
+        fee_treasury: Pubkey,
+    },
+    Integrator {
+        id: u64,
+        authority: Pubkey,
+        fee_bps: u16,
+        fee_treasury: Pubkey,
+        referral_share_bps: u16,
+        allowed_mints: Vec<Pubkey>,
+        relayers: Vec<Pubkey>,
+    },
+}
+
+// Mirrors of the `swap` argument and return types
+
+#[derive(BorshSerialize, Clone)]
+pub struct SwapCallback {
+    pub data: Vec<u8>,
+    pub accounts_len: u8,
+}
+
+#[derive(BorshSerialize, Clone, Copy)]
+pub struct VestingSchedule {
+    pub id: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+}
+
+#[derive(BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct SwapResult {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub amount_paid: u64,
+    pub fee: u64,
+}
+
+/// A mock aggregator instruction swapping `amount_in` out of `source` for `amount_out` into
+/// `destination`, both owned by `user`, against a fresh pool holding exactly `amount_out`.
+pub struct Route {
+    pub data: Vec<u8>,
+    pub accounts: Vec<AccountMeta>,
+    pub pool_input: Pubkey,
+    pub pool_output: Pubkey,
+}
+
+/// Accounts of a `swap` out of a vault, optional ones left out unless set.
+#[derive(Clone)]
+pub struct SwapAccounts {
+    pub payer: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub output_mint_token_program: Pubkey,
+    pub vault: Pubkey,
+    pub recipient: Pubkey,
+    pub recipient_token_account: Option<Pubkey>,
+    pub aggregator_program: Pubkey,
+    pub callback_program: Option<Pubkey>,
+    pub receipt: Option<Pubkey>,
+    /// Vesting account and its escrow
+    pub vesting: Option<(Pubkey, Pubkey)>,
+    /// Input and output price updates
+    pub price_updates: Option<(Pubkey, Pubkey)>,
+    /// Integrator and its fee treasury's token account
+    pub integrator: Option<(Pubkey, Pubkey)>,
+    /// Referrer, its referrer account and escrow
+    pub referrer: Option<(Pubkey, Pubkey, Pubkey)>,
+    pub memo_program: bool,
+    pub surplus_destination: Pubkey,
+}
+
+impl SwapAccounts {
+    pub fn vault_input_token_account(&self) -> Pubkey {
+        ata(&self.vault, &self.input_mint)
+    }
+
+    pub fn vault_output_token_account(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.vault,
+            &self.output_mint,
+            &self.output_mint_token_program,
+        )
+    }
+
+    pub fn mint_rate_limit(&self) -> Pubkey {
+        mint_rate_limit_address(&self.vault, &self.output_mint)
+    }
+
+    pub fn caller_rate_limit(&self) -> Pubkey {
+        caller_rate_limit_address(&self.vault, &self.payer, &self.output_mint)
+    }
+
+    pub fn mint_stats(&self) -> Pubkey {
+        mint_stats_address(&self.input_mint, &self.output_mint)
+    }
+
+    pub fn metas(&self) -> Vec<AccountMeta> {
+        let optional =
+            |address: Option<Pubkey>| address.map_or_else(none, |a| AccountMeta::new(a, false));
+        let optional_readonly = |address: Option<Pubkey>| {
+            address.map_or_else(none, |a| AccountMeta::new_readonly(a, false))
+        };
+        vec![
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new_readonly(self.input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.output_mint, false),
+            AccountMeta::new_readonly(self.output_mint_token_program, false),
+            AccountMeta::new(self.vault, false),
+            AccountMeta::new(self.vault_input_token_account(), false),
+            AccountMeta::new(self.vault_output_token_account(), false),
+            optional(self.recipient_token_account),
+            AccountMeta::new_readonly(self.recipient, false),
+            AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.aggregator_program, false),
+            AccountMeta::new_readonly(config_address(), false),
+            AccountMeta::new(self.mint_rate_limit(), false),
+            AccountMeta::new(self.caller_rate_limit(), false),
+            AccountMeta::new(self.mint_stats(), false),
+            optional_readonly(self.callback_program),
+            optional(self.receipt),
+            optional(self.vesting.map(|(vesting, _)| vesting)),
+            optional(self.vesting.map(|(_, escrow)| escrow)),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            optional_readonly(self.price_updates.map(|(input, _)| input)),
+            optional_readonly(self.price_updates.map(|(_, output)| output)),
+            optional_readonly(self.integrator.map(|(integrator, _)| integrator)),
+            optional(self.integrator.map(|(_, fee_treasury)| fee_treasury)),
+            optional_readonly(self.referrer.map(|(referrer, _, _)| referrer)),
+            optional(self.referrer.map(|(_, account, _)| account)),
+            optional(self.referrer.map(|(_, _, escrow)| escrow)),
+            optional_readonly(self.memo_program.then_some(MEMO_PROGRAM_ID)),
+            AccountMeta::new(self.surplus_destination, false),
+        ]
+    }
+}
+
+/// Arguments of `swap`.
+#[derive(BorshSerialize, Clone)]
+pub struct SwapArgs {
+    pub data: Vec<u8>,
+    pub amount: u64,
+    pub callback: Option<SwapCallback>,
+    pub receipt_id: Option<u64>,
+    pub vesting_schedule: Option<VestingSchedule>,
+    pub memo: Option<String>,
+}
+
+impl SwapArgs {
+    pub fn new(route: &Route, amount: u64) -> Self {
+        Self {
+            data: route.data.clone(),
+            amount,
+            callback: None,
+            receipt_id: None,
+            vesting_schedule: None,
+            memo: None,
+        }
+    }
+}
+
+/// The `swap` instruction, with the route's accounts, then `extra` ones such as callback
+/// accounts, after the named ones.
+pub fn swap_instruction(
+    accounts: &SwapAccounts,
+    args: SwapArgs,
+    route: &Route,
+    extra: &[AccountMeta],
+) -> Instruction {
+    let mut metas = accounts.metas();
+    metas.extend_from_slice(&route.accounts);
+    metas.extend_from_slice(extra);
+    instruction("swap", args, metas)
+}
+
+/// A swap of `amount_in` of a fresh input mint out of the shared vault, for `amount_out` of a
+/// fresh output mint, with the payer taking the surplus.
+pub struct SwapFixture {
+    pub payer: Keypair,
+    pub accounts: SwapAccounts,
+    pub route: Route,
+}
+
+impl SwapFixture {
+    pub fn instruction(&self, amount: u64) -> Instruction {
+        swap_instruction(
+            &self.accounts,
+            SwapArgs::new(&self.route, amount),
+            &self.route,
+            &[],
+        )
+    }
+}
+
+pub struct TestEnv {
+    pub svm: LiteSVM,
+    /// Upgrade authority of the program and authority and guardian of the config
+    pub authority: Keypair,
+    pub config: Pubkey,
+    next_change_id: u64,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        let authority = Keypair::new();
+        svm.airdrop(&authority.pubkey(), 100_000_000_000).unwrap();
+        let program_data = deploy_upgradeable(&mut svm, &authority.pubkey());
+        svm.add_program_from_file(SWAP_CALLER_PROGRAM_ID, SWAP_CALLER_PROGRAM_PATH)
+            .expect("run `anchor build` in ../jup-swap-program first");
+        svm.add_program_from_file(JUPITER_V6_AGG_PROGRAM_ID, MOCK_AGGREGATOR_PROGRAM_PATH)
+            .expect("build the mock aggregator with `cargo build-sbf` first");
+
+        let mut env = Self {
+            svm,
+            authority,
+            config: config_address(),
+            next_change_id: 0,
+        };
+        let authority = env.authority.insecure_clone();
+        env.send(
+            &[instruction(
+                "initialize_config",
+                (),
+                vec![
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new(env.config, false),
+                    AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),
+                    AccountMeta::new_readonly(program_data, false),
+                    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                ],
+            )],
+            &[&authority],
+        )
+        .expect("initialize_config failed");
+        env
+    }
+
+    /// Sends `instructions` with a fresh blockhash, so repeating a transaction isn't taken for
+    /// a duplicate, paid for by the first signer.
+    pub fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let mut all = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+        all.extend_from_slice(instructions);
+        let message = Message::new_with_blockhash(
+            &all,
+            Some(&signers[0].pubkey()),
+            &self.svm.latest_blockhash(),
+        );
+        let tx = VersionedTransaction::try_new(VersionedMessage::Legacy(message), signers).unwrap();
+        self.svm.send_transaction(tx)
+    }
+
+    pub fn wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        self.svm.airdrop(&wallet.pubkey(), 10_000_000_000).unwrap();
+        wallet
+    }
+
+    pub fn mint(&mut self) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        self.set_mint(mint, None);
+        mint
+    }
+
+    pub fn set_mint(&mut self, mint: Pubkey, freeze_authority: Option<Pubkey>) {
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(
+            Mint {
+                mint_authority: COption::None,
+                supply: u64::MAX,
+                decimals: 6,
+                is_initialized: true,
+                freeze_authority: freeze_authority.into(),
+            },
+            &mut data,
+        )
+        .unwrap();
+        self.set_program_account(mint, TOKEN_PROGRAM_ID, data);
+    }
+
+    /// The associated token account of `owner`, holding `amount`.
+    pub fn token_account(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+        let address = ata(owner, mint);
+        self.set_token_account(address, *mint, *owner, amount);
+        address
+    }
+
+    pub fn set_token_account(&mut self, address: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+        self.set_token_account_with_program(address, mint, owner, amount, TOKEN_PROGRAM_ID);
+    }
+
+    pub fn set_token_account_with_program(
+        &mut self,
+        address: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+        token_program: Pubkey,
+    ) {
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount::pack(
+            TokenAccount {
+                mint,
+                owner,
+                amount,
+                delegate: COption::None,
+                state: AccountState::Initialized,
+                is_native: COption::None,
+                delegated_amount: 0,
+                close_authority: COption::None,
+            },
+            &mut data,
+        )
+        .unwrap();
+        self.set_program_account(address, token_program, data);
+    }
+
+    /// Lets `delegate` spend `amount` out of a token account, as an `approve` by its owner would.
+    pub fn approve(&mut self, token_account: &Pubkey, delegate: &Pubkey, amount: u64) {
+        let mut account = self.svm.get_account(token_account).unwrap();
+        let mut state = TokenAccount::unpack(&account.data).unwrap();
+        state.delegate = COption::Some(*delegate);
+        state.delegated_amount = amount;
+        TokenAccount::pack(state, &mut account.data).unwrap();
+        self.svm.set_account(*token_account, account).unwrap();
+    }
+
+    pub fn set_program_account(&mut self, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+        let lamports = self.svm.minimum_balance_for_rent_exemption(data.len());
+        self.svm
+            .set_account(
+                address,
+                Account {
+                    lamports,
+                    data,
+                    owner,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    pub fn balance(&self, address: &Pubkey) -> u64 {
+        TokenAccount::unpack(&self.svm.get_account(address).unwrap().data)
+            .unwrap()
+            .amount
+    }
+
+    pub fn token_account_state(&self, address: &Pubkey) -> TokenAccount {
+        TokenAccount::unpack(&self.svm.get_account(address).unwrap().data).unwrap()
+    }
+
+    pub fn exists(&self, address: &Pubkey) -> bool {
+        self.svm
+            .get_account(address)
+            .is_some_and(|account| account.lamports > 0)
+    }
+
+    /// An account's data without its 8 byte discriminator.
+    pub fn data(&self, address: &Pubkey) -> Vec<u8> {
+        self.svm.get_account(address).unwrap().data[8..].to_vec()
+    }
+
+    /// A fully verified Pyth `PriceUpdateV2` account, the price being `price * 10^exponent`.
+    pub fn set_price_update(
+        &mut self,
+        address: Pubkey,
+        feed_id: [u8; 32],
+        price: i64,
+        exponent: i32,
+        publish_time: i64,
+    ) {
+        let mut data = discriminator("account:PriceUpdateV2").to_vec();
+        //write_authority, then the `Full` verification level
+        data.extend_from_slice(&[0; 32]);
+        data.push(1);
+        data.extend_from_slice(&feed_id);
+        data.extend_from_slice(&price.to_le_bytes());
+        //conf
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&exponent.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        //prev_publish_time, ema_price, ema_conf, then posted_slot
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&self.clock().slot.to_le_bytes());
+        self.set_program_account(address, PYTH_RECEIVER_PROGRAM_ID, data);
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.svm.get_sysvar::<Clock>()
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock().unix_timestamp
+    }
+
+    pub fn warp_seconds(&mut self, seconds: i64) {
+        let mut clock = self.clock();
+        clock.unix_timestamp += seconds;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn warp_slots(&mut self, slots: u64) {
+        let mut clock = self.clock();
+        clock.slot += slots;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// A mock aggregator route, see [`Route`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn route(
+        &mut self,
+        user: Pubkey,
+        source: Pubkey,
+        destination: Pubkey,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> Route {
+        let (pool_authority, _) =
+            Pubkey::find_program_address(&[b"pool"], &JUPITER_V6_AGG_PROGRAM_ID);
+        let pool_input = Pubkey::new_unique();
+        let pool_output = Pubkey::new_unique();
+        self.set_token_account(pool_input, input_mint, pool_authority, 0);
+        self.set_token_account(pool_output, output_mint, pool_authority, amount_out);
+
+        let mut data = ROUTE_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&amount_out.to_le_bytes());
+        Route {
+            data,
+            accounts: vec![
+                AccountMeta::new(user, false),
+                AccountMeta::new(source, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(pool_input, false),
+                AccountMeta::new(pool_output, false),
+                AccountMeta::new_readonly(pool_authority, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+            ],
+            pool_input,
+            pool_output,
+        }
+    }
+
+    /// See [`SwapFixture`].
+    pub fn swap_fixture(&mut self, amount_in: u64, amount_out: u64) -> SwapFixture {
+        self.swap_fixture_in(shared_vault(), amount_in, amount_out)
+    }
+
+    /// Same as [`TestEnv::swap_fixture`], out of `vault`.
+    pub fn swap_fixture_in(
+        &mut self,
+        vault: Pubkey,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> SwapFixture {
+        let payer = self.wallet();
+        let input_mint = self.mint();
+        let output_mint = self.mint();
+        let vault_input_token_account = self.token_account(&vault, &input_mint, amount_in);
+        let vault_output_token_account = self.token_account(&vault, &output_mint, 0);
+        let recipient = Pubkey::new_unique();
+        let recipient_token_account = self.token_account(&recipient, &output_mint, 0);
+        let surplus_destination = self.token_account(&payer.pubkey(), &output_mint, 0);
+        let route = self.route(
+            vault,
+            vault_input_token_account,
+            vault_output_token_account,
+            input_mint,
+            output_mint,
+            amount_in,
+            amount_out,
+        );
+        SwapFixture {
+            accounts: SwapAccounts {
+                payer: payer.pubkey(),
+                input_mint,
+                output_mint,
+                output_mint_token_program: TOKEN_PROGRAM_ID,
+                vault,
+                recipient,
+                recipient_token_account: Some(recipient_token_account),
+                aggregator_program: JUPITER_V6_AGG_PROGRAM_ID,
+                callback_program: None,
+                receipt: None,
+                vesting: None,
+                price_updates: None,
+                integrator: None,
+                referrer: None,
+                memo_program: false,
+                surplus_destination,
+            },
+            payer,
+            route,
+        }
+    }
+
+    /// Queues `change` from `proposer`, returning the pending change's address.
+    pub fn queue_change(
+        &mut self,
+        proposer: &Keypair,
+        integrator: Option<Pubkey>,
+        change: ConfigChange,
+    ) -> (Pubkey, TransactionResult) {
+        let id = self.next_change_id;
+        self.next_change_id += 1;
+        let pending_change = pending_change_address(&proposer.pubkey(), id);
+        let result = self.send(
+            &[instruction(
+                "queue_config_change",
+                (id, change),
+                vec![
+                    AccountMeta::new(proposer.pubkey(), true),
+                    AccountMeta::new_readonly(self.config, false),
+                    integrator.map_or_else(none, |i| AccountMeta::new_readonly(i, false)),
+                    AccountMeta::new(pending_change, false),
+                    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                ],
+            )],
+            &[proposer],
+        );
+        (pending_change, result)
+    }
+
+    pub fn execute_change(
+        &mut self,
+        proposer: &Pubkey,
+        integrator: Option<Pubkey>,
+        pending_change: Pubkey,
+    ) -> TransactionResult {
+        let payer = self.authority.insecure_clone();
+        self.send(
+            &[instruction(
+                "execute_config_change",
+                (),
+                vec![
+                    AccountMeta::new(*proposer, false),
+                    AccountMeta::new(self.config, false),
+                    integrator.map_or_else(none, |i| AccountMeta::new(i, false)),
+                    AccountMeta::new(pending_change, false),
+                ],
+            )],
+            &[&payer],
+        )
+    }
+
+    /// Funds the vault and a new route for another run of `fixture`'s swap.
+    pub fn refill_swap(&mut self, fixture: &mut SwapFixture, amount_in: u64, amount_out: u64) {
+        let accounts = &fixture.accounts;
+        let vault_input = accounts.vault_input_token_account();
+        self.set_token_account(vault_input, accounts.input_mint, accounts.vault, amount_in);
+        fixture.route = self.route(
+            accounts.vault,
+            vault_input,
+            accounts.vault_output_token_account(),
+            accounts.input_mint,
+            accounts.output_mint,
+            amount_in,
+            amount_out,
+        );
+    }
+
+    /// Onboards an integrator through the timelock, returning its address.
+    pub fn create_integrator(
+        &mut self,
+        id: u64,
+        authority: Pubkey,
+        fee_bps: u16,
+        fee_treasury: Pubkey,
+    ) -> Pubkey {
+        let pending_change = self.queue_and_wait(ConfigChange::NewIntegrator {
+            id,
+            authority,
+            fee_bps,
+            fee_treasury,
+        });
+        let payer = self.authority.insecure_clone();
+        let integrator = integrator_address(id);
+        self.send(
+            &[instruction(
+                "create_integrator",
+                (id, authority, fee_bps, fee_treasury),
+                vec![
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new(payer.pubkey(), false),
+                    AccountMeta::new_readonly(self.config, false),
+                    AccountMeta::new(integrator, false),
+                    AccountMeta::new(pending_change, false),
+                    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                ],
+            )],
+            &[&payer],
+        )
+        .expect("create_integrator failed");
+        integrator
+    }
+
+    /// Changes an integrator's settings through the timelock, as its `authority`.
+    pub fn apply_integrator_change(
+        &mut self,
+        authority: &Keypair,
+        integrator: Pubkey,
+        change: ConfigChange,
+    ) {
+        let (pending_change, result) = self.queue_change(authority, Some(integrator), change);
+        result.expect("queue_config_change failed");
+        self.warp_seconds(DEFAULT_TIMELOCK_DELAY);
+        self.execute_change(&authority.pubkey(), Some(integrator), pending_change)
+            .expect("execute_config_change failed");
+    }
+
+    /// Queues `change` from the config authority and lets it wait out the timelock, returning
+    /// the pending change's address.
+    pub fn queue_and_wait(&mut self, change: ConfigChange) -> Pubkey {
+        let authority = self.authority.insecure_clone();
+        let (pending_change, result) = self.queue_change(&authority, None, change);
+        result.expect("queue_config_change failed");
+        self.warp_seconds(DEFAULT_TIMELOCK_DELAY);
+        pending_change
+    }
+
+    /// Changes the config through the timelock.
+    pub fn apply_change(&mut self, change: ConfigChange) {
+        let pending_change = self.queue_and_wait(change);
+        let authority = self.authority.pubkey();
+        self.execute_change(&authority, None, pending_change)
+            .expect("execute_config_change failed");
+    }
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Deploys `jup_swap_program` under the upgradeable loader with `upgrade_authority`, as
+/// `initialize_config` requires, returning its program data address.
+fn deploy_upgradeable(svm: &mut LiteSVM, upgrade_authority: &Pubkey) -> Pubkey {
+    let elf = std::fs::read(CPI_SWAP_PROGRAM_PATH)
+        .expect("run `anchor build` in ../jup-swap-program first");
+    let (program_data, _) =
+        Pubkey::find_program_address(&[CPI_SWAP_PROGRAM_ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+
+    // UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address }, then the ELF
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(upgrade_authority.as_ref());
+    data.extend_from_slice(&elf);
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    svm.set_account(
+        program_data,
+        Account {
+            lamports,
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+
+    // UpgradeableLoaderState::Program { programdata_address }
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(program_data.as_ref());
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    svm.set_account(
+        CPI_SWAP_PROGRAM_ID,
+        Account {
+            lamports,
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: true,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+    program_data
+}
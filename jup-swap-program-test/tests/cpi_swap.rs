@@ -0,0 +1,131 @@
+//! Swaps through `jup_swap_program` from the `swap_caller` example program, with the mock
+//! aggregator standing in for Jupiter. Build the programs first:
+//!
+//! anchor build (in ../jup-swap-program)
+//! cargo build-sbf --manifest-path programs/mock-aggregator/Cargo.toml --sbf-out-dir target/deploy
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use {
+    common::{
+        discriminator, shared_vault, SwapResult, TestEnv, CPI_SWAP_PROGRAM_ID,
+        JUPITER_V6_AGG_PROGRAM_ID, SWAP_CALLER_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    solana_instruction::{AccountMeta, Instruction},
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    spl_associated_token_account::ID as ATA_ID,
+};
+
+#[derive(BorshSerialize)]
+struct SwapExactIxData {
+    route_data: Vec<u8>,
+    amount: u64,
+}
+
+#[test]
+fn swap_through_caller_program() {
+    let mut env = TestEnv::new();
+    let payer = env.wallet();
+    let input_mint = env.mint();
+    let output_mint = env.mint();
+
+    let amount_in = 1_000_000;
+    let amount_out = 2_500_000;
+    let amount = 2_000_000;
+
+    let vault = shared_vault();
+    let vault_input_token_account = env.token_account(&vault, &input_mint, amount_in);
+    let vault_output_token_account = env.token_account(&vault, &output_mint, 0);
+    let recipient = Pubkey::new_unique();
+    let recipient_token_account = env.token_account(&recipient, &output_mint, 0);
+    //The route delivers more than the payout, the rest comes back to the payer
+    let payer_output_token_account = env.token_account(&payer.pubkey(), &output_mint, 0);
+    let route = env.route(
+        vault,
+        vault_input_token_account,
+        vault_output_token_account,
+        input_mint,
+        output_mint,
+        amount_in,
+        amount_out,
+    );
+    let mint_stats = common::mint_stats_address(&input_mint, &output_mint);
+
+    let mut data = discriminator("global:swap_exact").to_vec();
+    data.extend_from_slice(
+        &borsh::to_vec(&SwapExactIxData {
+            route_data: route.data.clone(),
+            amount,
+        })
+        .unwrap(),
+    );
+
+    let mut accounts = vec![
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new_readonly(input_mint, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(output_mint, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(vault_input_token_account, false),
+        AccountMeta::new(vault_output_token_account, false),
+        AccountMeta::new(recipient_token_account, false),
+        AccountMeta::new_readonly(recipient, false),
+        AccountMeta::new_readonly(ATA_ID, false),
+        AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+        AccountMeta::new_readonly(env.config, false),
+        AccountMeta::new(common::mint_rate_limit_address(&vault, &output_mint), false),
+        AccountMeta::new(
+            common::caller_rate_limit_address(&vault, &payer.pubkey(), &output_mint),
+            false,
+        ),
+        AccountMeta::new(mint_stats, false),
+        AccountMeta::new(payer_output_token_account, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(CPI_SWAP_PROGRAM_ID, false),
+    ];
+    //Route accounts, forwarded to the mock aggregator
+    accounts.extend_from_slice(&route.accounts);
+    let ix = Instruction {
+        program_id: SWAP_CALLER_PROGRAM_ID,
+        accounts,
+        data,
+    };
+
+    let meta = env
+        .send(&[ix], &[&payer])
+        .unwrap_or_else(|err| panic!("swap through the caller program failed: {err:#?}"));
+
+    //The caller doesn't set any return data, so what's left is jup_swap_program's
+    assert_eq!(meta.return_data.program_id, CPI_SWAP_PROGRAM_ID);
+    let result = SwapResult::try_from_slice(&meta.return_data.data).unwrap();
+    assert_eq!(
+        result,
+        SwapResult {
+            amount_in,
+            amount_out,
+            amount_paid: amount,
+            fee: 0,
+        }
+    );
+
+    assert_eq!(env.balance(&recipient_token_account), amount);
+    assert_eq!(env.balance(&vault_input_token_account), 0);
+    assert_eq!(env.balance(&vault_output_token_account), 0);
+    assert_eq!(
+        env.balance(&payer_output_token_account),
+        amount_out - amount
+    );
+    assert_eq!(env.balance(&route.pool_input), amount_in);
+
+    // input mint, output mint, then the volumes and the swap count
+    let stats = env.data(&mint_stats);
+    let volume_in = u128::from_le_bytes(stats[64..80].try_into().unwrap());
+    let volume_out = u128::from_le_bytes(stats[80..96].try_into().unwrap());
+    let swap_count = u64::from_le_bytes(stats[96..104].try_into().unwrap());
+    assert_eq!(volume_in, u128::from(amount_in));
+    assert_eq!(volume_out, u128::from(amount_out));
+    assert_eq!(swap_count, 1);
+}
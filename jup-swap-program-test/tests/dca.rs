@@ -0,0 +1,185 @@
+//! `create_dca` and `execute_dca_cycle`: recurring swaps out of an escrow, run by keepers.
+
+mod common;
+
+use {
+    common::{
+        assert_error, ata, instruction, mint_rate_limit_address, pda, shared_vault, TestEnv,
+        ATA_PROGRAM_ID, JUPITER_V6_AGG_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const DCA_ID: u64 = 3;
+const AMOUNT_PER_CYCLE: u64 = 1_000_000;
+const TOTAL_INPUT: u64 = 5 * AMOUNT_PER_CYCLE / 2;
+const MIN_OUT_PER_CYCLE: u64 = 2_000_000;
+const INTERVAL: i64 = 3600;
+
+struct Fixture {
+    env: TestEnv,
+    keeper: Keypair,
+    recipient: Pubkey,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    dca: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let owner = env.wallet();
+        let keeper = env.wallet();
+        let recipient = Pubkey::new_unique();
+        let input_mint = env.mint();
+        let output_mint = env.mint();
+        env.token_account(&owner.pubkey(), &input_mint, TOTAL_INPUT);
+        env.token_account(&recipient, &output_mint, 0);
+        let vault = shared_vault();
+        env.token_account(&vault, &input_mint, 0);
+        env.token_account(&vault, &output_mint, 0);
+
+        let dca = pda(&[b"dca", owner.pubkey().as_ref(), &DCA_ID.to_le_bytes()]);
+        let ix = instruction(
+            "create_dca",
+            (
+                DCA_ID,
+                TOTAL_INPUT,
+                AMOUNT_PER_CYCLE,
+                INTERVAL,
+                MIN_OUT_PER_CYCLE,
+            ),
+            vec![
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new_readonly(recipient, false),
+                AccountMeta::new(dca, false),
+                AccountMeta::new_readonly(input_mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(output_mint, false),
+                AccountMeta::new(ata(&owner.pubkey(), &input_mint), false),
+                AccountMeta::new(ata(&dca, &input_mint), false),
+                AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        env.send(&[ix], &[&owner]).expect("create_dca failed");
+
+        Self {
+            env,
+            keeper,
+            recipient,
+            input_mint,
+            output_mint,
+            dca,
+        }
+    }
+
+    fn execute_cycle(&mut self, amount_in: u64, amount_out: u64) -> TransactionResult {
+        let vault = shared_vault();
+        let route = self.env.route(
+            vault,
+            ata(&vault, &self.input_mint),
+            ata(&vault, &self.output_mint),
+            self.input_mint,
+            self.output_mint,
+            amount_in,
+            amount_out,
+        );
+        let mut accounts = vec![
+            AccountMeta::new(self.keeper.pubkey(), true),
+            AccountMeta::new(self.dca, false),
+            AccountMeta::new_readonly(self.input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.output_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&self.dca, &self.input_mint), false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(ata(&vault, &self.input_mint), false),
+            AccountMeta::new(ata(&vault, &self.output_mint), false),
+            AccountMeta::new(ata(&self.recipient, &self.output_mint), false),
+            AccountMeta::new_readonly(self.recipient, false),
+            AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.env.config, false),
+            AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+            AccountMeta::new(mint_rate_limit_address(&vault, &self.output_mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+        accounts.extend_from_slice(&route.accounts);
+        let ix = instruction("execute_dca_cycle", route.data.clone(), accounts);
+        let keeper = self.keeper.insecure_clone();
+        self.env.send(&[ix], &[&keeper])
+    }
+
+    /// Input spent and output received so far.
+    fn progress(&self) -> (u64, u64) {
+        let data = self.env.data(&self.dca);
+        (
+            u64::from_le_bytes(data[176..184].try_into().unwrap()),
+            u64::from_le_bytes(data[184..192].try_into().unwrap()),
+        )
+    }
+}
+
+#[test]
+fn runs_cycles_until_escrow_is_spent() {
+    let mut fixture = Fixture::new();
+    fixture
+        .execute_cycle(AMOUNT_PER_CYCLE, MIN_OUT_PER_CYCLE)
+        .expect("first cycle failed");
+    fixture.env.warp_seconds(INTERVAL);
+    fixture
+        .execute_cycle(AMOUNT_PER_CYCLE, MIN_OUT_PER_CYCLE + 1)
+        .expect("second cycle failed");
+    fixture.env.warp_seconds(INTERVAL);
+    //The last cycle only has half a cycle left, so half the minimum out is enough
+    fixture
+        .execute_cycle(AMOUNT_PER_CYCLE / 2, MIN_OUT_PER_CYCLE / 2)
+        .expect("last cycle failed");
+
+    let output = 2 * MIN_OUT_PER_CYCLE + 1 + MIN_OUT_PER_CYCLE / 2;
+    assert_eq!(fixture.progress(), (TOTAL_INPUT, output));
+    assert_eq!(
+        fixture
+            .env
+            .balance(&ata(&fixture.recipient, &fixture.output_mint)),
+        output
+    );
+    assert_eq!(
+        fixture.env.balance(&ata(&fixture.dca, &fixture.input_mint)),
+        0
+    );
+
+    fixture.env.warp_seconds(INTERVAL);
+    let result = fixture.execute_cycle(0, 0);
+    assert_error(result, "InsufficientInput");
+}
+
+#[test]
+fn rejects_cycle_before_interval() {
+    let mut fixture = Fixture::new();
+    fixture
+        .execute_cycle(AMOUNT_PER_CYCLE, MIN_OUT_PER_CYCLE)
+        .expect("first cycle failed");
+    fixture.env.warp_seconds(INTERVAL - 1);
+    let result = fixture.execute_cycle(AMOUNT_PER_CYCLE, MIN_OUT_PER_CYCLE);
+    assert_error(result, "CycleNotReady");
+}
+
+#[test]
+fn rejects_cycle_below_minimum_out() {
+    let mut fixture = Fixture::new();
+    let result = fixture.execute_cycle(AMOUNT_PER_CYCLE, MIN_OUT_PER_CYCLE - 1);
+    assert_error(result, "SlippageExceeded");
+    assert_eq!(fixture.progress(), (0, 0));
+}
+
+#[test]
+fn rejects_route_spending_less_than_the_cycle() {
+    let mut fixture = Fixture::new();
+    let result = fixture.execute_cycle(AMOUNT_PER_CYCLE - 1, MIN_OUT_PER_CYCLE);
+    assert_error(result, "RouteInputMismatch");
+}
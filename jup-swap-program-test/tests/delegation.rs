@@ -0,0 +1,219 @@
+//! `create_delegation` and `swap_from_delegate`: keepers swapping an owner's tokens up to the
+//! cap the owner approved and no worse than the rate the owner set.
+
+mod common;
+
+use {
+    common::{
+        assert_error, ata, instruction, mint_rate_limit_address, pda, shared_vault, TestEnv,
+        ATA_PROGRAM_ID, JUPITER_V6_AGG_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const CAP: u64 = 1_000_000;
+// At least one output token per input token
+const RATE_IN: u64 = CAP;
+const MIN_OUT_PER_RATE_IN: u64 = CAP;
+
+struct Fixture {
+    env: TestEnv,
+    owner: Keypair,
+    keeper: Keypair,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    delegation: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut fixture = Self::without_delegation();
+        fixture
+            .create_delegation(RATE_IN, MIN_OUT_PER_RATE_IN)
+            .expect("create_delegation failed");
+        fixture
+    }
+
+    fn without_delegation() -> Self {
+        let mut env = TestEnv::new();
+        let owner = env.wallet();
+        let keeper = env.wallet();
+        let input_mint = env.mint();
+        let output_mint = env.mint();
+        let owner_input_token_account = env.token_account(&owner.pubkey(), &input_mint, 10 * CAP);
+        env.token_account(&owner.pubkey(), &output_mint, 0);
+        let vault = shared_vault();
+        env.token_account(&vault, &input_mint, 0);
+        env.token_account(&vault, &output_mint, 0);
+
+        let delegation = pda(&[b"delegation", owner_input_token_account.as_ref()]);
+        Self {
+            env,
+            owner,
+            keeper,
+            input_mint,
+            output_mint,
+            delegation,
+        }
+    }
+
+    fn create_delegation(&mut self, rate_in: u64, min_out_per_rate_in: u64) -> TransactionResult {
+        let owner = self.owner.insecure_clone();
+        let ix = instruction(
+            "create_delegation",
+            (self.keeper.pubkey(), CAP, rate_in, min_out_per_rate_in),
+            vec![
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new(self.delegation, false),
+                AccountMeta::new_readonly(self.input_mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(self.output_mint, false),
+                AccountMeta::new(ata(&owner.pubkey(), &self.input_mint), false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        self.env.send(&[ix], &[&owner])
+    }
+
+    /// `amount_out` is what the route delivers, the keeper asks for at least `min_out`.
+    fn swap_from_delegate(
+        &mut self,
+        keeper: &Keypair,
+        amount_in: u64,
+        amount_out: u64,
+        min_out: u64,
+    ) -> TransactionResult {
+        let owner = self.owner.pubkey();
+        let vault = shared_vault();
+        let route = self.env.route(
+            vault,
+            ata(&vault, &self.input_mint),
+            ata(&vault, &self.output_mint),
+            self.input_mint,
+            self.output_mint,
+            amount_in,
+            amount_out,
+        );
+        let mut accounts = vec![
+            AccountMeta::new(keeper.pubkey(), true),
+            AccountMeta::new_readonly(owner, false),
+            AccountMeta::new_readonly(self.delegation, false),
+            AccountMeta::new_readonly(self.input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.output_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&owner, &self.input_mint), false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(ata(&vault, &self.input_mint), false),
+            AccountMeta::new(ata(&vault, &self.output_mint), false),
+            AccountMeta::new(ata(&owner, &self.output_mint), false),
+            AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.env.config, false),
+            AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+            AccountMeta::new(mint_rate_limit_address(&vault, &self.output_mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+        accounts.extend_from_slice(&route.accounts);
+        let ix = instruction(
+            "swap_from_delegate",
+            (route.data.clone(), amount_in, min_out),
+            accounts,
+        );
+        self.env.send(&[ix], &[keeper])
+    }
+}
+
+#[test]
+fn keeper_swaps_within_cap_into_owner_account() {
+    let mut fixture = Fixture::new();
+    let keeper = fixture.keeper.insecure_clone();
+    fixture
+        .swap_from_delegate(&keeper, CAP / 2, 3 * CAP, 3 * CAP)
+        .expect("swap_from_delegate failed");
+
+    let owner = fixture.owner.pubkey();
+    let input = fixture
+        .env
+        .token_account_state(&ata(&owner, &fixture.input_mint));
+    assert_eq!(input.amount, 10 * CAP - CAP / 2);
+    assert_eq!(input.delegated_amount, CAP / 2);
+    assert_eq!(
+        fixture.env.balance(&ata(&owner, &fixture.output_mint)),
+        3 * CAP
+    );
+    assert_eq!(
+        fixture
+            .env
+            .balance(&ata(&shared_vault(), &fixture.output_mint)),
+        0
+    );
+
+    //The rest of the cap can still be used
+    fixture
+        .swap_from_delegate(&keeper, CAP / 2, 3 * CAP, 3 * CAP)
+        .expect("swap_from_delegate failed");
+}
+
+#[test]
+fn rejects_swap_over_cap() {
+    let mut fixture = Fixture::new();
+    let keeper = fixture.keeper.insecure_clone();
+    let result = fixture.swap_from_delegate(&keeper, CAP + 1, CAP + 1, CAP + 1);
+    assert_error(result, "DelegateNotApproved");
+
+    fixture
+        .swap_from_delegate(&keeper, CAP, CAP, CAP)
+        .expect("swap_from_delegate failed");
+    let result = fixture.swap_from_delegate(&keeper, 1, 1, 1);
+    assert_error(result, "DelegateNotApproved");
+}
+
+#[test]
+fn rejects_other_keeper() {
+    let mut fixture = Fixture::new();
+    let stranger = fixture.env.wallet();
+    let result = fixture.swap_from_delegate(&stranger, CAP / 2, CAP, CAP);
+    assert_error(result, "ConstraintHasOne");
+}
+
+#[test]
+fn rejects_second_delegation_on_same_account() {
+    let mut fixture = Fixture::new();
+    assert!(fixture
+        .create_delegation(RATE_IN, MIN_OUT_PER_RATE_IN)
+        .is_err());
+}
+
+#[test]
+fn rejects_route_below_owner_rate() {
+    let mut fixture = Fixture::new();
+    let keeper = fixture.keeper.insecure_clone();
+    //The keeper's own minimum doesn't loosen the owner's
+    let result = fixture.swap_from_delegate(&keeper, CAP / 2, CAP / 2 - 1, 0);
+    assert_error(result, "SlippageExceeded");
+
+    fixture
+        .swap_from_delegate(&keeper, CAP / 2, CAP / 2, 0)
+        .expect("swap_from_delegate failed");
+}
+
+#[test]
+fn keeper_min_out_tightens_owner_rate() {
+    let mut fixture = Fixture::new();
+    let keeper = fixture.keeper.insecure_clone();
+    let result = fixture.swap_from_delegate(&keeper, CAP / 2, CAP, CAP + 1);
+    assert_error(result, "SlippageExceeded");
+}
+
+#[test]
+fn rejects_delegation_without_rate() {
+    let mut fixture = Fixture::without_delegation();
+    let result = fixture.create_delegation(0, MIN_OUT_PER_RATE_IN);
+    assert_error(result, "ZeroAmount");
+    let result = fixture.create_delegation(RATE_IN, 0);
+    assert_error(result, "ZeroAmount");
+}
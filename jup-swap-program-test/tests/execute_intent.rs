@@ -0,0 +1,296 @@
+//! `execute_intent`: swaps signed off-chain by the user and relayed by someone else.
+
+mod common;
+
+use borsh::BorshSerialize;
+use {
+    common::{
+        assert_error, ata, instruction, mint_rate_limit_address, none, pda, shared_vault, TestEnv,
+        ATA_PROGRAM_ID, CPI_SWAP_PROGRAM_ID, INSTRUCTIONS_SYSVAR_ID, JUPITER_V6_AGG_PROGRAM_ID,
+        SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_pubkey::{pubkey, Pubkey},
+    solana_signer::Signer,
+};
+
+const ED25519_PROGRAM_ID: Pubkey = pubkey!("Ed25519SigVerify111111111111111111111111111");
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 2_000_000;
+const RELAYER_FEE: u64 = 10_000;
+
+#[derive(BorshSerialize, Clone)]
+struct SwapIntent {
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount: u64,
+    min_out: u64,
+    recipient: Pubkey,
+    deadline: i64,
+    nonce: u64,
+    relayer_fee: u64,
+}
+
+impl SwapIntent {
+    fn message(&self) -> Vec<u8> {
+        let mut message = CPI_SWAP_PROGRAM_ID.to_bytes().to_vec();
+        self.serialize(&mut message).unwrap();
+        message
+    }
+}
+
+/// The ed25519 precompile instruction verifying `signer`'s signature over `message`, with the
+/// public key, signature and message all inside it.
+fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    const HEADER_LEN: u16 = 16;
+    let public_key_offset = HEADER_LEN;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+
+    let mut data = vec![1, 0];
+    for offset in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signer.sign_message(message).as_ref());
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ED25519_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+struct Fixture {
+    env: TestEnv,
+    user: Keypair,
+    relayer: Keypair,
+    intent: SwapIntent,
+    route: common::Route,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let user = env.wallet();
+        let relayer = env.wallet();
+        let input_mint = env.mint();
+        let output_mint = env.mint();
+        let recipient = Pubkey::new_unique();
+
+        let user_input_token_account = env.token_account(&user.pubkey(), &input_mint, AMOUNT_IN);
+        let intent_authority = pda(&[b"intent_authority", user.pubkey().as_ref()]);
+        env.approve(&user_input_token_account, &intent_authority, AMOUNT_IN);
+
+        let vault = shared_vault();
+        let vault_input_token_account = env.token_account(&vault, &input_mint, 0);
+        let vault_output_token_account = env.token_account(&vault, &output_mint, 0);
+        env.token_account(&recipient, &output_mint, 0);
+        env.token_account(&relayer.pubkey(), &output_mint, 0);
+        let route = env.route(
+            vault,
+            vault_input_token_account,
+            vault_output_token_account,
+            input_mint,
+            output_mint,
+            AMOUNT_IN,
+            AMOUNT_OUT,
+        );
+
+        let intent = SwapIntent {
+            input_mint,
+            output_mint,
+            amount: AMOUNT_IN,
+            min_out: AMOUNT_OUT - RELAYER_FEE,
+            recipient,
+            deadline: env.now() + 60,
+            nonce: 0,
+            relayer_fee: RELAYER_FEE,
+        };
+        Self {
+            env,
+            user,
+            relayer,
+            intent,
+            route,
+        }
+    }
+
+    fn execute_intent(&self, intent: &SwapIntent) -> Instruction {
+        let user = self.user.pubkey();
+        let vault = shared_vault();
+        let mut accounts = vec![
+            AccountMeta::new(self.relayer.pubkey(), true),
+            AccountMeta::new_readonly(user, false),
+            AccountMeta::new(pda(&[b"nonce", user.as_ref()]), false),
+            AccountMeta::new_readonly(intent.input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(intent.output_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&user, &intent.input_mint), false),
+            AccountMeta::new_readonly(pda(&[b"intent_authority", user.as_ref()]), false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(ata(&vault, &intent.input_mint), false),
+            AccountMeta::new(ata(&vault, &intent.output_mint), false),
+            AccountMeta::new(ata(&intent.recipient, &intent.output_mint), false),
+            AccountMeta::new_readonly(intent.recipient, false),
+            AccountMeta::new(ata(&self.relayer.pubkey(), &intent.output_mint), false),
+            AccountMeta::new_readonly(INSTRUCTIONS_SYSVAR_ID, false),
+            AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.env.config, false),
+            AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+            AccountMeta::new(mint_rate_limit_address(&vault, &intent.output_mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+        accounts.extend_from_slice(&self.route.accounts);
+        instruction(
+            "execute_intent",
+            (intent.clone(), self.route.data.clone()),
+            accounts,
+        )
+    }
+
+    fn relay(
+        &mut self,
+        signer: &Keypair,
+        signed: &SwapIntent,
+        submitted: &SwapIntent,
+    ) -> TransactionResult {
+        let instructions = [
+            ed25519_instruction(signer, &signed.message()),
+            self.execute_intent(submitted),
+        ];
+        let relayer = self.relayer.insecure_clone();
+        self.env.send(&instructions, &[&relayer])
+    }
+
+    fn nonce(&self) -> u64 {
+        let data = self
+            .env
+            .data(&pda(&[b"nonce", self.user.pubkey().as_ref()]));
+        u64::from_le_bytes(data[32..40].try_into().unwrap())
+    }
+}
+
+#[test]
+fn relays_signed_intent() {
+    let mut fixture = Fixture::new();
+    let user = fixture.user.insecure_clone();
+    let intent = fixture.intent.clone();
+    fixture
+        .relay(&user, &intent, &intent)
+        .expect("execute_intent failed");
+
+    let env = &fixture.env;
+    assert_eq!(env.balance(&ata(&user.pubkey(), &intent.input_mint)), 0);
+    assert_eq!(
+        env.balance(&ata(&intent.recipient, &intent.output_mint)),
+        AMOUNT_OUT - RELAYER_FEE
+    );
+    assert_eq!(
+        env.balance(&ata(&fixture.relayer.pubkey(), &intent.output_mint)),
+        RELAYER_FEE
+    );
+    assert_eq!(env.balance(&ata(&shared_vault(), &intent.output_mint)), 0);
+    assert_eq!(fixture.nonce(), 1);
+}
+
+#[test]
+fn rejects_intent_signed_by_another_wallet() {
+    let mut fixture = Fixture::new();
+    let forger = Keypair::new();
+    let intent = fixture.intent.clone();
+    let result = fixture.relay(&forger, &intent, &intent);
+    assert_error(result, "SignatureMismatch");
+}
+
+#[test]
+fn rejects_intent_changed_after_signing() {
+    let mut fixture = Fixture::new();
+    let user = fixture.user.insecure_clone();
+    let signed = fixture.intent.clone();
+    let mut submitted = signed.clone();
+    submitted.relayer_fee = AMOUNT_OUT - signed.min_out + 1;
+    submitted.min_out = 0;
+    let result = fixture.relay(&user, &signed, &submitted);
+    assert_error(result, "SignatureMismatch");
+}
+
+#[test]
+fn rejects_replayed_intent() {
+    let mut fixture = Fixture::new();
+    let user = fixture.user.insecure_clone();
+    let intent = fixture.intent.clone();
+    fixture
+        .relay(&user, &intent, &intent)
+        .expect("execute_intent failed");
+
+    //Fund everything again so only the spent nonce stands in the way
+    let input_token_account = ata(&user.pubkey(), &intent.input_mint);
+    fixture.env.set_token_account(
+        input_token_account,
+        intent.input_mint,
+        user.pubkey(),
+        AMOUNT_IN,
+    );
+    let intent_authority = pda(&[b"intent_authority", user.pubkey().as_ref()]);
+    fixture
+        .env
+        .approve(&input_token_account, &intent_authority, AMOUNT_IN);
+    let result = fixture.relay(&user, &intent, &intent);
+    assert_error(result, "InvalidNonce");
+    assert_eq!(fixture.nonce(), 1);
+}
+
+#[test]
+fn rejects_expired_intent() {
+    let mut fixture = Fixture::new();
+    let user = fixture.user.insecure_clone();
+    let intent = fixture.intent.clone();
+    fixture.env.warp_seconds(61);
+    let result = fixture.relay(&user, &intent, &intent);
+    assert_error(result, "IntentExpired");
+}
+
+#[test]
+fn rejects_intent_without_signature_instruction() {
+    let mut fixture = Fixture::new();
+    let intent = fixture.intent.clone();
+    let ix = fixture.execute_intent(&intent);
+    let relayer = fixture.relayer.insecure_clone();
+    let result = fixture.env.send(&[ix], &[&relayer]);
+    assert_error(result, "InvalidSignatureInstruction");
+}
+
+#[test]
+fn pays_recipient_everything_without_relayer_account() {
+    let mut fixture = Fixture::new();
+    let user = fixture.user.insecure_clone();
+    let intent = fixture.intent.clone();
+    let mut ix = fixture.execute_intent(&intent);
+    //No relayer token account, so the whole output goes to the recipient
+    ix.accounts[14] = none();
+    let instructions = [ed25519_instruction(&user, &intent.message()), ix];
+    let relayer = fixture.relayer.insecure_clone();
+    fixture
+        .env
+        .send(&instructions, &[&relayer])
+        .expect("execute_intent failed");
+    assert_eq!(
+        fixture
+            .env
+            .balance(&ata(&intent.recipient, &intent.output_mint)),
+        AMOUNT_OUT
+    );
+}
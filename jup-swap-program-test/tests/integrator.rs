@@ -0,0 +1,296 @@
+//! Integrator swaps: out of the integrator's own vault, restricted to its mints and relayers,
+//! paying it a fee, and `withdraw_integrator_token` sweeping that vault.
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::{
+        assert_error, ata, instruction, integrator_vault, shared_vault, ConfigChange, SwapFixture,
+        SwapResult, TestEnv, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const INTEGRATOR_ID: u64 = 9;
+const FEE_BPS: u16 = 100;
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_PAID: u64 = 2_000_000;
+const FEE: u64 = AMOUNT_PAID * FEE_BPS as u64 / 10_000;
+const SURPLUS: u64 = 80_000;
+
+struct Fixture {
+    env: TestEnv,
+    authority: Keypair,
+    fee_treasury: Pubkey,
+    integrator: Pubkey,
+    swap: SwapFixture,
+}
+
+impl Fixture {
+    /// An integrator taking `FEE_BPS` and a swap out of its vault delivering the payout, the
+    /// fee and `SURPLUS`.
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let authority = env.wallet();
+        let fee_treasury = Pubkey::new_unique();
+        let integrator =
+            env.create_integrator(INTEGRATOR_ID, authority.pubkey(), FEE_BPS, fee_treasury);
+        let mut swap = env.swap_fixture_in(
+            integrator_vault(&integrator),
+            AMOUNT_IN,
+            AMOUNT_PAID + FEE + SURPLUS,
+        );
+        let fee_treasury_token_account =
+            env.token_account(&fee_treasury, &swap.accounts.output_mint, 0);
+        swap.accounts.integrator = Some((integrator, fee_treasury_token_account));
+
+        Self {
+            env,
+            authority,
+            fee_treasury,
+            integrator,
+            swap,
+        }
+    }
+
+    fn swap(&mut self) -> TransactionResult {
+        let ix = self.swap.instruction(AMOUNT_PAID);
+        self.env.send(&[ix], &[&self.swap.payer])
+    }
+
+    /// Restricts the integrator's swaps to `allowed_mints` and `relayers`.
+    fn restrict(&mut self, allowed_mints: Vec<Pubkey>, relayers: Vec<Pubkey>) {
+        let authority = self.authority.insecure_clone();
+        self.env.apply_integrator_change(
+            &authority,
+            self.integrator,
+            ConfigChange::Integrator {
+                id: INTEGRATOR_ID,
+                authority: authority.pubkey(),
+                fee_bps: FEE_BPS,
+                fee_treasury: self.fee_treasury,
+                referral_share_bps: 0,
+                allowed_mints,
+                relayers,
+            },
+        );
+    }
+
+    fn withdraw_integrator_token(
+        &mut self,
+        authority: &Keypair,
+        destination: Pubkey,
+        amount: u64,
+    ) -> TransactionResult {
+        let mint = self.swap.accounts.output_mint;
+        let vault = integrator_vault(&self.integrator);
+        let ix = instruction(
+            "withdraw_integrator_token",
+            amount,
+            vec![
+                AccountMeta::new_readonly(authority.pubkey(), true),
+                AccountMeta::new_readonly(self.integrator, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(vault, false),
+                AccountMeta::new(ata(&vault, &mint), false),
+                AccountMeta::new(destination, false),
+            ],
+        );
+        self.env.send(&[ix], &[authority])
+    }
+}
+
+#[test]
+fn pays_integrator_fee_out_of_its_vault() {
+    let mut fixture = Fixture::new();
+    let meta = fixture.swap().expect("swap failed");
+
+    let accounts = &fixture.swap.accounts;
+    let (_, fee_treasury_token_account) = accounts.integrator.unwrap();
+    assert_eq!(
+        fixture
+            .env
+            .balance(&accounts.recipient_token_account.unwrap()),
+        AMOUNT_PAID
+    );
+    assert_eq!(fixture.env.balance(&fee_treasury_token_account), FEE);
+    assert_eq!(fixture.env.balance(&accounts.surplus_destination), SURPLUS);
+    assert_eq!(
+        SwapResult::try_from_slice(&meta.return_data.data)
+            .unwrap()
+            .fee,
+        FEE
+    );
+}
+
+#[test]
+fn rejects_integrator_swap_out_of_shared_vault() {
+    let mut fixture = Fixture::new();
+    let accounts = &fixture.swap.accounts;
+    let (input_mint, output_mint) = (accounts.input_mint, accounts.output_mint);
+    fixture
+        .env
+        .token_account(&shared_vault(), &input_mint, AMOUNT_IN);
+    fixture.env.token_account(&shared_vault(), &output_mint, 0);
+    fixture.swap.accounts.vault = shared_vault();
+    let result = fixture.swap();
+    assert_error(result, "InvalidVault");
+}
+
+#[test]
+fn rejects_route_not_covering_fee() {
+    let mut fixture = Fixture::new();
+    let mut swap = fixture.env.swap_fixture_in(
+        integrator_vault(&fixture.integrator),
+        AMOUNT_IN,
+        AMOUNT_PAID + FEE - 1,
+    );
+    let fee_treasury_token_account =
+        fixture
+            .env
+            .token_account(&fixture.fee_treasury, &swap.accounts.output_mint, 0);
+    swap.accounts.integrator = Some((fixture.integrator, fee_treasury_token_account));
+    fixture.swap = swap;
+    let result = fixture.swap();
+    assert_error(result, "OutputBelowPayout");
+}
+
+#[test]
+fn rejects_fee_account_not_owned_by_fee_treasury() {
+    let mut fixture = Fixture::new();
+    let payer = fixture.swap.payer.pubkey();
+    let output_mint = fixture.swap.accounts.output_mint;
+    let payer_token_account = ata(&payer, &output_mint);
+    fixture.swap.accounts.integrator = Some((fixture.integrator, payer_token_account));
+    let result = fixture.swap();
+    assert_error(result, "InvalidFeeTreasury");
+}
+
+#[test]
+fn rejects_mint_outside_integrator_allowlist() {
+    let mut fixture = Fixture::new();
+    let input_mint = fixture.swap.accounts.input_mint;
+    fixture.restrict(vec![input_mint], vec![]);
+    let result = fixture.swap();
+    assert_error(result, "MintNotAllowed");
+
+    let output_mint = fixture.swap.accounts.output_mint;
+    fixture.restrict(vec![input_mint, output_mint], vec![]);
+    fixture.swap().expect("swap failed");
+}
+
+#[test]
+fn rejects_relayer_outside_integrator_allowlist() {
+    let mut fixture = Fixture::new();
+    fixture.restrict(vec![], vec![Pubkey::new_unique()]);
+    let result = fixture.swap();
+    assert_error(result, "RelayerNotAllowed");
+
+    let payer = fixture.swap.payer.pubkey();
+    fixture.restrict(vec![], vec![payer]);
+    fixture.swap().expect("swap failed");
+}
+
+#[test]
+fn rejects_integrator_change_from_config_authority() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.env.authority.insecure_clone();
+    let (_, result) = fixture.env.queue_change(
+        &authority,
+        Some(fixture.integrator),
+        ConfigChange::Integrator {
+            id: INTEGRATOR_ID,
+            authority: authority.pubkey(),
+            fee_bps: 10_000,
+            fee_treasury: authority.pubkey(),
+            referral_share_bps: 0,
+            allowed_mints: vec![],
+            relayers: vec![],
+        },
+    );
+    assert_error(result, "Unauthorized");
+}
+
+#[test]
+fn rotates_integrator_authority() {
+    let mut fixture = Fixture::new();
+    let old_authority = fixture.authority.insecure_clone();
+    let new_authority = fixture.env.wallet();
+    let change = |authority: &Keypair, fee_bps| ConfigChange::Integrator {
+        id: INTEGRATOR_ID,
+        authority: authority.pubkey(),
+        fee_bps,
+        fee_treasury: fixture.fee_treasury,
+        referral_share_bps: 0,
+        allowed_mints: vec![],
+        relayers: vec![],
+    };
+    //A change the old authority queued before handing the integrator over
+    let (stale_change, result) = fixture.env.queue_change(
+        &old_authority,
+        Some(fixture.integrator),
+        change(&old_authority, 10_000),
+    );
+    result.expect("queue_config_change failed");
+    fixture.env.apply_integrator_change(
+        &old_authority,
+        fixture.integrator,
+        change(&new_authority, FEE_BPS),
+    );
+
+    let result = fixture.env.execute_change(
+        &old_authority.pubkey(),
+        Some(fixture.integrator),
+        stale_change,
+    );
+    assert_error(result, "Unauthorized");
+    let (_, result) = fixture.env.queue_change(
+        &old_authority,
+        Some(fixture.integrator),
+        change(&old_authority, FEE_BPS),
+    );
+    assert_error(result, "Unauthorized");
+
+    fixture.authority = new_authority;
+    fixture.restrict(vec![], vec![]);
+    let destination = fixture.env.token_account(
+        &old_authority.pubkey(),
+        &fixture.swap.accounts.output_mint,
+        0,
+    );
+    let result = fixture.withdraw_integrator_token(&old_authority, destination, 0);
+    assert_error(result, "Unauthorized");
+}
+
+#[test]
+fn integrator_authority_withdraws_from_its_vault() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority.insecure_clone();
+    let output_mint = fixture.swap.accounts.output_mint;
+    let destination = fixture
+        .env
+        .token_account(&authority.pubkey(), &output_mint, 0);
+    let vault_output = fixture.swap.accounts.vault_output_token_account();
+    fixture.env.set_token_account(
+        vault_output,
+        output_mint,
+        integrator_vault(&fixture.integrator),
+        500,
+    );
+
+    let stranger = fixture.env.wallet();
+    let result = fixture.withdraw_integrator_token(&stranger, destination, 500);
+    assert_error(result, "Unauthorized");
+
+    fixture
+        .withdraw_integrator_token(&authority, destination, 500)
+        .expect("withdraw_integrator_token failed");
+    assert_eq!(fixture.env.balance(&destination), 500);
+    assert_eq!(fixture.env.balance(&vault_output), 0);
+}
@@ -0,0 +1,188 @@
+//! `create_invoice` and `pay_invoice`: paying a merchant an exact amount out of any token.
+
+mod common;
+
+use {
+    common::{
+        assert_error, ata, instruction, mint_rate_limit_address, pda, shared_vault, TestEnv,
+        ATA_PROGRAM_ID, JUPITER_V6_AGG_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const INVOICE_ID: u64 = 11;
+const INVOICE_AMOUNT: u64 = 2_000_000;
+const PAYER_INPUT: u64 = 5_000_000;
+const EXPIRES_IN: i64 = 3600;
+
+struct Fixture {
+    env: TestEnv,
+    merchant: Keypair,
+    payer: Keypair,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    invoice: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let merchant = env.wallet();
+        let payer = env.wallet();
+        let input_mint = env.mint();
+        let output_mint = env.mint();
+        env.token_account(&payer.pubkey(), &input_mint, PAYER_INPUT);
+        let vault = shared_vault();
+        env.token_account(&vault, &input_mint, 0);
+        env.token_account(&vault, &output_mint, 0);
+        let invoice = pda(&[
+            b"invoice",
+            merchant.pubkey().as_ref(),
+            &INVOICE_ID.to_le_bytes(),
+        ]);
+
+        Self {
+            env,
+            merchant,
+            payer,
+            input_mint,
+            output_mint,
+            invoice,
+        }
+    }
+
+    fn create_invoice(&mut self, expiry: i64) -> TransactionResult {
+        let ix = instruction(
+            "create_invoice",
+            (INVOICE_ID, INVOICE_AMOUNT, expiry, [7u8; 32]),
+            vec![
+                AccountMeta::new(self.merchant.pubkey(), true),
+                AccountMeta::new(self.invoice, false),
+                AccountMeta::new_readonly(self.output_mint, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        let merchant = self.merchant.insecure_clone();
+        self.env.send(&[ix], &[&merchant])
+    }
+
+    /// Creates the invoice, due in an hour.
+    fn with_invoice() -> Self {
+        let mut fixture = Self::new();
+        let expiry = fixture.env.now() + EXPIRES_IN;
+        fixture
+            .create_invoice(expiry)
+            .expect("create_invoice failed");
+        fixture
+    }
+
+    /// Pays the invoice with `amount_in`, through a route spending `route_in` for `route_out`.
+    fn pay_invoice(&mut self, amount_in: u64, route_in: u64, route_out: u64) -> TransactionResult {
+        let vault = shared_vault();
+        let route = self.env.route(
+            vault,
+            ata(&vault, &self.input_mint),
+            ata(&vault, &self.output_mint),
+            self.input_mint,
+            self.output_mint,
+            route_in,
+            route_out,
+        );
+        let payer = self.payer.pubkey();
+        let merchant = self.merchant.pubkey();
+        let mut accounts = vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(merchant, false),
+            AccountMeta::new(self.invoice, false),
+            AccountMeta::new_readonly(self.input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.output_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&payer, &self.input_mint), false),
+            AccountMeta::new(ata(&payer, &self.output_mint), false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(ata(&vault, &self.input_mint), false),
+            AccountMeta::new(ata(&vault, &self.output_mint), false),
+            AccountMeta::new(ata(&merchant, &self.output_mint), false),
+            AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.env.config, false),
+            AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+            AccountMeta::new(mint_rate_limit_address(&vault, &self.output_mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+        accounts.extend_from_slice(&route.accounts);
+        let ix = instruction("pay_invoice", (route.data.clone(), amount_in), accounts);
+        let payer = self.payer.insecure_clone();
+        self.env.send(&[ix], &[&payer])
+    }
+
+    fn paid(&self) -> bool {
+        self.env.data(&self.invoice)[120] == 1
+    }
+}
+
+#[test]
+fn pays_merchant_exact_amount_and_refunds_the_rest() {
+    let mut fixture = Fixture::with_invoice();
+    let payer = fixture.payer.pubkey();
+    let merchant = fixture.merchant.pubkey();
+    //The route only spends part of the input and delivers more than the invoice
+    fixture
+        .pay_invoice(1_500_000, 1_000_000, INVOICE_AMOUNT + 300_000)
+        .expect("pay_invoice failed");
+
+    assert!(fixture.paid());
+    assert_eq!(
+        fixture.env.balance(&ata(&merchant, &fixture.output_mint)),
+        INVOICE_AMOUNT
+    );
+    assert_eq!(
+        fixture.env.balance(&ata(&payer, &fixture.output_mint)),
+        300_000
+    );
+    assert_eq!(
+        fixture.env.balance(&ata(&payer, &fixture.input_mint)),
+        PAYER_INPUT - 1_000_000
+    );
+    let vault = shared_vault();
+    assert_eq!(fixture.env.balance(&ata(&vault, &fixture.input_mint)), 0);
+    assert_eq!(fixture.env.balance(&ata(&vault, &fixture.output_mint)), 0);
+}
+
+#[test]
+fn rejects_paying_invoice_twice() {
+    let mut fixture = Fixture::with_invoice();
+    fixture
+        .pay_invoice(1_000_000, 1_000_000, INVOICE_AMOUNT)
+        .expect("pay_invoice failed");
+    let result = fixture.pay_invoice(1_000_000, 1_000_000, INVOICE_AMOUNT);
+    assert_error(result, "InvoiceAlreadyPaid");
+}
+
+#[test]
+fn rejects_route_delivering_less_than_invoice() {
+    let mut fixture = Fixture::with_invoice();
+    let result = fixture.pay_invoice(1_000_000, 1_000_000, INVOICE_AMOUNT - 1);
+    assert_error(result, "SlippageExceeded");
+    assert!(!fixture.paid());
+}
+
+#[test]
+fn rejects_expired_invoice() {
+    let mut fixture = Fixture::with_invoice();
+    fixture.env.warp_seconds(EXPIRES_IN);
+    let result = fixture.pay_invoice(1_000_000, 1_000_000, INVOICE_AMOUNT);
+    assert_error(result, "InvoiceExpired");
+}
+
+#[test]
+fn rejects_invoice_already_expired_when_created() {
+    let mut fixture = Fixture::new();
+    let now = fixture.env.now();
+    let result = fixture.create_invoice(now);
+    assert_error(result, "InvalidExpiry");
+}
@@ -0,0 +1,157 @@
+//! `create_order` and `fill_order`: limit orders escrowed by the owner and filled by keepers.
+
+mod common;
+
+use {
+    common::{
+        assert_error, ata, instruction, mint_rate_limit_address, pda, shared_vault, TestEnv,
+        ATA_PROGRAM_ID, JUPITER_V6_AGG_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const MIN_AMOUNT_OUT: u64 = 2_000_000;
+const ORDER_ID: u64 = 7;
+
+struct Fixture {
+    env: TestEnv,
+    owner: Keypair,
+    keeper: Keypair,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    order: Pubkey,
+    expiry: i64,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let owner = env.wallet();
+        let keeper = env.wallet();
+        let input_mint = env.mint();
+        let output_mint = env.mint();
+        env.token_account(&owner.pubkey(), &input_mint, AMOUNT_IN);
+        env.token_account(&owner.pubkey(), &output_mint, 0);
+        let vault = shared_vault();
+        env.token_account(&vault, &input_mint, 0);
+        env.token_account(&vault, &output_mint, 0);
+
+        let order = pda(&[b"order", owner.pubkey().as_ref(), &ORDER_ID.to_le_bytes()]);
+        let expiry = env.now() + 3600;
+        let ix = instruction(
+            "create_order",
+            (ORDER_ID, AMOUNT_IN, MIN_AMOUNT_OUT, expiry),
+            vec![
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new(order, false),
+                AccountMeta::new_readonly(input_mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(output_mint, false),
+                AccountMeta::new(ata(&owner.pubkey(), &input_mint), false),
+                AccountMeta::new(ata(&order, &input_mint), false),
+                AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        env.send(&[ix], &[&owner]).expect("create_order failed");
+
+        Self {
+            env,
+            owner,
+            keeper,
+            input_mint,
+            output_mint,
+            order,
+            expiry,
+        }
+    }
+
+    fn fill(&mut self, route_amount_in: u64, route_amount_out: u64) -> TransactionResult {
+        let owner = self.owner.pubkey();
+        let vault = shared_vault();
+        let route = self.env.route(
+            vault,
+            ata(&vault, &self.input_mint),
+            ata(&vault, &self.output_mint),
+            self.input_mint,
+            self.output_mint,
+            route_amount_in,
+            route_amount_out,
+        );
+        let mut accounts = vec![
+            AccountMeta::new(self.keeper.pubkey(), true),
+            AccountMeta::new(owner, false),
+            AccountMeta::new(self.order, false),
+            AccountMeta::new_readonly(self.input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.output_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&self.order, &self.input_mint), false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(ata(&vault, &self.input_mint), false),
+            AccountMeta::new(ata(&vault, &self.output_mint), false),
+            AccountMeta::new(ata(&owner, &self.output_mint), false),
+            AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.env.config, false),
+            AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+            AccountMeta::new(mint_rate_limit_address(&vault, &self.output_mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+        accounts.extend_from_slice(&route.accounts);
+        let ix = instruction("fill_order", route.data.clone(), accounts);
+        let keeper = self.keeper.insecure_clone();
+        self.env.send(&[ix], &[&keeper])
+    }
+}
+
+#[test]
+fn fills_order_at_limit_price() {
+    let mut fixture = Fixture::new();
+    assert_eq!(
+        fixture
+            .env
+            .balance(&ata(&fixture.order, &fixture.input_mint)),
+        AMOUNT_IN
+    );
+    fixture
+        .fill(AMOUNT_IN, MIN_AMOUNT_OUT)
+        .expect("fill_order failed");
+
+    let env = &fixture.env;
+    assert_eq!(
+        env.balance(&ata(&fixture.owner.pubkey(), &fixture.output_mint)),
+        MIN_AMOUNT_OUT
+    );
+    assert_eq!(env.balance(&ata(&shared_vault(), &fixture.input_mint)), 0);
+    assert!(!env.exists(&fixture.order));
+    assert!(!env.exists(&ata(&fixture.order, &fixture.input_mint)));
+}
+
+#[test]
+fn rejects_route_spending_less_than_the_order() {
+    let mut fixture = Fixture::new();
+    let result = fixture.fill(AMOUNT_IN - 1, MIN_AMOUNT_OUT);
+    assert_error(result, "RouteInputMismatch");
+}
+
+#[test]
+fn rejects_fill_below_limit_price() {
+    let mut fixture = Fixture::new();
+    let result = fixture.fill(AMOUNT_IN, MIN_AMOUNT_OUT - 1);
+    assert_error(result, "LimitPriceNotMet");
+}
+
+#[test]
+fn rejects_expired_order() {
+    let mut fixture = Fixture::new();
+    let expiry = fixture.expiry;
+    let now = fixture.env.now();
+    fixture.env.warp_seconds(expiry - now);
+    let result = fixture.fill(AMOUNT_IN, MIN_AMOUNT_OUT);
+    assert_error(result, "OrderExpired");
+}
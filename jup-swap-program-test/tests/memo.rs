@@ -0,0 +1,58 @@
+//! Memos `swap` attaches to its payout through the memo program.
+
+mod common;
+
+use {
+    common::{assert_error, swap_instruction, SwapArgs, SwapFixture, TestEnv},
+    litesvm::types::TransactionResult,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 2_000_000;
+const MAX_MEMO_LEN: usize = 256;
+
+fn swap_with_memo(env: &mut TestEnv, fixture: &SwapFixture, memo: &str) -> TransactionResult {
+    let mut args = SwapArgs::new(&fixture.route, AMOUNT_OUT);
+    args.memo = Some(memo.to_string());
+    let ix = swap_instruction(&fixture.accounts, args, &fixture.route, &[]);
+    env.send(&[ix], &[&fixture.payer])
+}
+
+#[test]
+fn logs_memo_with_payout() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    fixture.accounts.memo_program = true;
+    let meta = swap_with_memo(&mut env, &fixture, "invoice 2024-117").expect("swap failed");
+    assert!(meta
+        .logs
+        .iter()
+        .any(|log| log.contains("\"invoice 2024-117\"")));
+    let recipient_token_account = fixture.accounts.recipient_token_account.unwrap();
+    assert_eq!(env.balance(&recipient_token_account), AMOUNT_OUT);
+}
+
+#[test]
+fn accepts_memo_of_max_len() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    fixture.accounts.memo_program = true;
+    swap_with_memo(&mut env, &fixture, &"m".repeat(MAX_MEMO_LEN)).expect("swap failed");
+}
+
+#[test]
+fn rejects_memo_over_max_len() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    fixture.accounts.memo_program = true;
+    let result = swap_with_memo(&mut env, &fixture, &"m".repeat(MAX_MEMO_LEN + 1));
+    assert_error(result, "MemoTooLong");
+}
+
+#[test]
+fn rejects_memo_without_memo_program() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let result = swap_with_memo(&mut env, &fixture, "invoice 2024-117");
+    assert_error(result, "MissingMemoProgram");
+}
@@ -0,0 +1,150 @@
+//! `swap` refusing to pay out in mints that could trap or claw back the recipient's tokens,
+//! unless the config trusts them.
+
+mod common;
+
+use {
+    common::{assert_error, ConfigChange, SwapFixture, TestEnv, TOKEN_2022_PROGRAM_ID},
+    solana_program_option::COption,
+    solana_program_pack::Pack,
+    solana_pubkey::Pubkey,
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    spl_token::state::Mint,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 2_000_000;
+// Token-2022 extension types
+const DEFAULT_ACCOUNT_STATE: u16 = 6;
+const NON_TRANSFERABLE: u16 = 9;
+const PERMANENT_DELEGATE: u16 = 12;
+// Risk flags of the config's `blocked_mint_risks`
+const DEFAULT_BLOCKED_MINT_RISKS: u8 = 1 | 2 | 4;
+const FREEZE_AUTHORITY: u8 = 8;
+
+/// Switches the fixture's output to a Token-2022 mint with the extension `extension_type`
+/// holding `value`, along with the vault's, recipient's and payer's token accounts.
+fn set_token_2022_output(
+    env: &mut TestEnv,
+    fixture: &mut SwapFixture,
+    extension_type: u16,
+    value: &[u8],
+) {
+    let mint = Pubkey::new_unique();
+    let mut data = vec![0; Mint::LEN];
+    Mint::pack(
+        Mint {
+            mint_authority: COption::None,
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        },
+        &mut data,
+    )
+    .unwrap();
+    //Extensions start after the size of a token account and the account type
+    data.resize(165, 0);
+    data.push(1);
+    data.extend_from_slice(&extension_type.to_le_bytes());
+    data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    data.extend_from_slice(value);
+    env.set_program_account(mint, TOKEN_2022_PROGRAM_ID, data);
+
+    let accounts = &mut fixture.accounts;
+    accounts.output_mint = mint;
+    accounts.output_mint_token_program = TOKEN_2022_PROGRAM_ID;
+    let token_account = |owner: &Pubkey| {
+        get_associated_token_address_with_program_id(owner, &mint, &TOKEN_2022_PROGRAM_ID)
+    };
+    let recipient_token_account = token_account(&accounts.recipient);
+    let surplus_destination = token_account(&accounts.payer);
+    for (address, owner) in [
+        (accounts.vault_output_token_account(), accounts.vault),
+        (recipient_token_account, accounts.recipient),
+        (surplus_destination, accounts.payer),
+    ] {
+        env.set_token_account_with_program(address, mint, owner, 0, TOKEN_2022_PROGRAM_ID);
+    }
+    accounts.recipient_token_account = Some(recipient_token_account);
+    accounts.surplus_destination = surplus_destination;
+}
+
+fn set_mint_risks(env: &mut TestEnv, blocked_mint_risks: u8, trusted_mints: Vec<Pubkey>) {
+    env.apply_change(ConfigChange::MintRisks {
+        blocked_mint_risks,
+        trusted_mints,
+    });
+}
+
+fn assert_dangerous(env: &mut TestEnv, fixture: &SwapFixture) {
+    let result = env.send(&[fixture.instruction(AMOUNT_OUT)], &[&fixture.payer]);
+    assert_error(result, "DangerousMint");
+}
+
+#[test]
+fn rejects_permanent_delegate_mint() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let delegate = Pubkey::new_unique();
+    set_token_2022_output(
+        &mut env,
+        &mut fixture,
+        PERMANENT_DELEGATE,
+        delegate.as_ref(),
+    );
+    assert_dangerous(&mut env, &fixture);
+}
+
+#[test]
+fn rejects_non_transferable_mint() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    set_token_2022_output(&mut env, &mut fixture, NON_TRANSFERABLE, &[]);
+    assert_dangerous(&mut env, &fixture);
+}
+
+#[test]
+fn rejects_mint_freezing_new_accounts() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    //`AccountState::Frozen`
+    set_token_2022_output(&mut env, &mut fixture, DEFAULT_ACCOUNT_STATE, &[2]);
+    assert_dangerous(&mut env, &fixture);
+}
+
+#[test]
+fn freeze_authority_is_only_blocked_when_opted_in() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let output_mint = fixture.accounts.output_mint;
+    env.set_mint(output_mint, Some(Pubkey::new_unique()));
+
+    set_mint_risks(
+        &mut env,
+        DEFAULT_BLOCKED_MINT_RISKS | FREEZE_AUTHORITY,
+        vec![],
+    );
+    assert_dangerous(&mut env, &fixture);
+
+    set_mint_risks(&mut env, DEFAULT_BLOCKED_MINT_RISKS, vec![]);
+    env.send(&[fixture.instruction(AMOUNT_OUT)], &[&fixture.payer])
+        .expect("swap failed");
+}
+
+#[test]
+fn pays_out_in_trusted_mint_despite_its_risks() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let output_mint = fixture.accounts.output_mint;
+    env.set_mint(output_mint, Some(Pubkey::new_unique()));
+    set_mint_risks(
+        &mut env,
+        DEFAULT_BLOCKED_MINT_RISKS | FREEZE_AUTHORITY,
+        vec![output_mint],
+    );
+    env.send(&[fixture.instruction(AMOUNT_OUT)], &[&fixture.payer])
+        .expect("swap failed");
+    let recipient_token_account = fixture.accounts.recipient_token_account.unwrap();
+    assert_eq!(env.balance(&recipient_token_account), AMOUNT_OUT);
+}
@@ -0,0 +1,99 @@
+//! Running totals `swap` keeps for each pair of input and output mints.
+
+mod common;
+
+use {
+    common::{assert_error, integrator_vault, TestEnv},
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 2_000_000;
+
+/// The fields of the program's `MintStats` the tests look at.
+#[derive(Debug, PartialEq)]
+struct Stats {
+    volume_in: u128,
+    volume_out: u128,
+    swap_count: u64,
+    total_fees: u128,
+    last_slot: u64,
+}
+
+fn stats(env: &TestEnv, mint_stats: &Pubkey) -> Stats {
+    // input mint and output mint first
+    let data = env.data(mint_stats);
+    Stats {
+        volume_in: u128::from_le_bytes(data[64..80].try_into().unwrap()),
+        volume_out: u128::from_le_bytes(data[80..96].try_into().unwrap()),
+        swap_count: u64::from_le_bytes(data[96..104].try_into().unwrap()),
+        total_fees: u128::from_le_bytes(data[104..120].try_into().unwrap()),
+        last_slot: u64::from_le_bytes(data[120..128].try_into().unwrap()),
+    }
+}
+
+#[test]
+fn accumulates_swaps_of_mint_pair() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    env.send(&[fixture.instruction(AMOUNT_OUT)], &[&fixture.payer])
+        .expect("swap failed");
+    let mint_stats = fixture.accounts.mint_stats();
+    assert_eq!(
+        stats(&env, &mint_stats),
+        Stats {
+            volume_in: u128::from(AMOUNT_IN),
+            volume_out: u128::from(AMOUNT_OUT),
+            swap_count: 1,
+            total_fees: 0,
+            last_slot: env.clock().slot,
+        }
+    );
+
+    env.warp_slots(10);
+    env.refill_swap(&mut fixture, 3_000_000, 5_000_000);
+    env.send(&[fixture.instruction(4_000_000)], &[&fixture.payer])
+        .expect("swap failed");
+    assert_eq!(
+        stats(&env, &mint_stats),
+        Stats {
+            volume_in: 4_000_000,
+            volume_out: 7_000_000,
+            swap_count: 2,
+            total_fees: 0,
+            last_slot: env.clock().slot,
+        }
+    );
+}
+
+#[test]
+fn records_integrator_fees() {
+    let mut env = TestEnv::new();
+    let authority = env.wallet();
+    let fee_treasury = Pubkey::new_unique();
+    let integrator = env.create_integrator(9, authority.pubkey(), 100, fee_treasury);
+    let mut fixture = env.swap_fixture_in(integrator_vault(&integrator), AMOUNT_IN, AMOUNT_OUT);
+    let fee_treasury_token_account =
+        env.token_account(&fee_treasury, &fixture.accounts.output_mint, 0);
+    fixture.accounts.integrator = Some((integrator, fee_treasury_token_account));
+
+    //1% on top of the payout
+    env.send(&[fixture.instruction(1_000_000)], &[&fixture.payer])
+        .expect("swap failed");
+    let stats = stats(&env, &fixture.accounts.mint_stats());
+    assert_eq!(stats.total_fees, 10_000);
+    assert_eq!(stats.volume_out, u128::from(AMOUNT_OUT));
+}
+
+#[test]
+fn skips_failed_swaps() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let output_mint = fixture.accounts.output_mint;
+    fixture.accounts.surplus_destination =
+        env.token_account(&Pubkey::new_unique(), &output_mint, 0);
+    let result = env.send(&[fixture.instruction(AMOUNT_IN)], &[&fixture.payer]);
+    assert_error(result, "InvalidSurplusDestination");
+    assert!(!env.exists(&fixture.accounts.mint_stats()));
+}
@@ -0,0 +1,118 @@
+//! `swap` checking its rate against Pyth prices when the caller passes price updates.
+
+mod common;
+
+use {
+    common::{assert_error, none, ConfigChange, OracleFeed, SwapFixture, TestEnv},
+    solana_pubkey::Pubkey,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_PAID: u64 = 2_000_000;
+const INPUT_FEED: [u8; 32] = [1; 32];
+const OUTPUT_FEED: [u8; 32] = [2; 32];
+const MAX_DEVIATION_BPS: u16 = 100;
+const MAX_AGE: i64 = 60;
+// Input at $2 and output at $1, both with the same decimals
+const INPUT_PRICE: i64 = 200_000_000;
+const OUTPUT_PRICE: i64 = 100_000_000;
+const EXPONENT: i32 = -8;
+
+/// A swap of `AMOUNT_IN` for `amount_out` with price updates for both mints, published
+/// `age` seconds ago.
+fn fixture_with_prices(env: &mut TestEnv, amount_out: u64, age: i64) -> SwapFixture {
+    let mut fixture = env.swap_fixture(AMOUNT_IN, amount_out);
+    env.apply_change(ConfigChange::OracleConfig {
+        max_deviation_bps: MAX_DEVIATION_BPS,
+        max_age: MAX_AGE,
+        feeds: vec![
+            OracleFeed {
+                mint: fixture.accounts.input_mint,
+                feed_id: INPUT_FEED,
+            },
+            OracleFeed {
+                mint: fixture.accounts.output_mint,
+                feed_id: OUTPUT_FEED,
+            },
+        ],
+    });
+    let publish_time = env.now() - age;
+    let input_price_update = Pubkey::new_unique();
+    let output_price_update = Pubkey::new_unique();
+    env.set_price_update(
+        input_price_update,
+        INPUT_FEED,
+        INPUT_PRICE,
+        EXPONENT,
+        publish_time,
+    );
+    env.set_price_update(
+        output_price_update,
+        OUTPUT_FEED,
+        OUTPUT_PRICE,
+        EXPONENT,
+        publish_time,
+    );
+    fixture.accounts.price_updates = Some((input_price_update, output_price_update));
+    fixture
+}
+
+#[test]
+fn accepts_swap_at_oracle_rate() {
+    let mut env = TestEnv::new();
+    //1% off the oracle rate is still within the allowed deviation
+    let fixture = fixture_with_prices(&mut env, AMOUNT_PAID + AMOUNT_PAID / 100, 0);
+    env.send(&[fixture.instruction(AMOUNT_PAID)], &[&fixture.payer])
+        .expect("swap failed");
+    let recipient_token_account = fixture.accounts.recipient_token_account.unwrap();
+    assert_eq!(env.balance(&recipient_token_account), AMOUNT_PAID);
+}
+
+#[test]
+fn rejects_swap_deviating_from_oracle_rate() {
+    let mut env = TestEnv::new();
+    let fixture = fixture_with_prices(&mut env, AMOUNT_PAID - AMOUNT_PAID / 50, 0);
+    let result = env.send(&[fixture.instruction(AMOUNT_PAID / 2)], &[&fixture.payer]);
+    assert_error(result, "OracleDeviationExceeded");
+}
+
+#[test]
+fn rejects_stale_price() {
+    let mut env = TestEnv::new();
+    let fixture = fixture_with_prices(&mut env, AMOUNT_PAID, MAX_AGE + 1);
+    let result = env.send(&[fixture.instruction(AMOUNT_PAID)], &[&fixture.payer]);
+    assert_error(result, "StaleOraclePrice");
+}
+
+#[test]
+fn rejects_price_update_for_another_feed() {
+    let mut env = TestEnv::new();
+    let mut fixture = fixture_with_prices(&mut env, AMOUNT_PAID, 0);
+    let (input, output) = fixture.accounts.price_updates.unwrap();
+    fixture.accounts.price_updates = Some((output, input));
+    let result = env.send(&[fixture.instruction(AMOUNT_PAID)], &[&fixture.payer]);
+    assert_error(result, "OracleFeedMismatch");
+}
+
+#[test]
+fn rejects_price_update_not_owned_by_pyth() {
+    let mut env = TestEnv::new();
+    let fixture = fixture_with_prices(&mut env, AMOUNT_PAID, 0);
+    let (input, _) = fixture.accounts.price_updates.unwrap();
+    let mut account = env.svm.get_account(&input).unwrap();
+    account.owner = Pubkey::new_unique();
+    env.svm.set_account(input, account).unwrap();
+    let result = env.send(&[fixture.instruction(AMOUNT_PAID)], &[&fixture.payer]);
+    assert_error(result, "InvalidOracleAccount");
+}
+
+#[test]
+fn rejects_price_update_for_one_mint_only() {
+    let mut env = TestEnv::new();
+    let fixture = fixture_with_prices(&mut env, AMOUNT_PAID, 0);
+    let mut ix = fixture.instruction(AMOUNT_PAID);
+    //output_price_update
+    ix.accounts[22] = none();
+    let result = env.send(&[ix], &[&fixture.payer]);
+    assert_error(result, "OracleMismatch");
+}
@@ -0,0 +1,317 @@
+//! Portfolios: `rebalance_portfolio` moving holdings towards their target weights at oracle
+//! prices, and the authority setting targets and withdrawing.
+
+mod common;
+
+use {
+    borsh::BorshSerialize,
+    common::{
+        assert_error, ata, instruction, pda, ConfigChange, OracleFeed, TestEnv,
+        JUPITER_V6_AGG_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const PORTFOLIO_ID: u64 = 2;
+const MAX_SLIPPAGE_BPS: u16 = 100;
+const MAX_ORACLE_AGE: i64 = 60;
+// Every asset at $1, with the same decimals
+const PRICE: i64 = 100_000_000;
+const EXPONENT: i32 = -8;
+const HOLDING_A: u64 = 3_000_000;
+const HOLDING_B: u64 = 1_000_000;
+
+/// Mirror of the program's `PortfolioAsset`.
+#[derive(BorshSerialize, Clone, Copy)]
+struct PortfolioAsset {
+    mint: Pubkey,
+    target_bps: u16,
+}
+
+struct Fixture {
+    env: TestEnv,
+    authority: Keypair,
+    keeper: Keypair,
+    portfolio: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    /// Each mint's price update
+    price_updates: Vec<(Pubkey, Pubkey)>,
+}
+
+impl Fixture {
+    /// A portfolio targeting half of its value in each of two mints, holding three times more
+    /// of the first.
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let authority = env.wallet();
+        let keeper = env.wallet();
+        let mint_a = env.mint();
+        let mint_b = env.mint();
+        let portfolio = pda(&[
+            b"portfolio",
+            authority.pubkey().as_ref(),
+            &PORTFOLIO_ID.to_le_bytes(),
+        ]);
+        env.token_account(&portfolio, &mint_a, HOLDING_A);
+        env.token_account(&portfolio, &mint_b, HOLDING_B);
+
+        let mut fixture = Self {
+            env,
+            authority,
+            keeper,
+            portfolio,
+            mint_a,
+            mint_b,
+            price_updates: vec![],
+        };
+        fixture.set_price_feeds(&[mint_a, mint_b]);
+        fixture
+            .create_portfolio(fixture.even_targets())
+            .expect("create_portfolio failed");
+        fixture
+    }
+
+    fn even_targets(&self) -> Vec<PortfolioAsset> {
+        vec![
+            PortfolioAsset {
+                mint: self.mint_a,
+                target_bps: 5_000,
+            },
+            PortfolioAsset {
+                mint: self.mint_b,
+                target_bps: 5_000,
+            },
+        ]
+    }
+
+    /// Configures a feed for each mint and posts a $1 price update for it.
+    fn set_price_feeds(&mut self, mints: &[Pubkey]) {
+        let feeds: Vec<OracleFeed> = mints
+            .iter()
+            .enumerate()
+            .map(|(i, mint)| OracleFeed {
+                mint: *mint,
+                feed_id: [i as u8 + 1; 32],
+            })
+            .collect();
+        self.env.apply_change(ConfigChange::OracleConfig {
+            max_deviation_bps: MAX_SLIPPAGE_BPS,
+            max_age: MAX_ORACLE_AGE,
+            feeds: feeds.clone(),
+        });
+        self.price_updates = feeds
+            .iter()
+            .map(|feed| (feed.mint, Pubkey::new_unique()))
+            .collect();
+        self.post_prices(0);
+    }
+
+    fn post_prices(&mut self, age: i64) {
+        let publish_time = self.env.now() - age;
+        for (i, (_, price_update)) in self.price_updates.clone().into_iter().enumerate() {
+            self.env.set_price_update(
+                price_update,
+                [i as u8 + 1; 32],
+                PRICE,
+                EXPONENT,
+                publish_time,
+            );
+        }
+    }
+
+    fn price_update(&self, mint: &Pubkey) -> Pubkey {
+        self.price_updates
+            .iter()
+            .find(|(price_mint, _)| price_mint == mint)
+            .map(|(_, price_update)| *price_update)
+            .unwrap()
+    }
+
+    fn create_portfolio(&mut self, assets: Vec<PortfolioAsset>) -> TransactionResult {
+        let ix = instruction(
+            "create_portfolio",
+            (PORTFOLIO_ID, assets, MAX_SLIPPAGE_BPS),
+            vec![
+                AccountMeta::new(self.authority.pubkey(), true),
+                AccountMeta::new(self.portfolio, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        let authority = self.authority.insecure_clone();
+        self.env.send(&[ix], &[&authority])
+    }
+
+    fn set_targets(&mut self, assets: Vec<PortfolioAsset>) -> TransactionResult {
+        let ix = instruction(
+            "set_portfolio_targets",
+            (assets, MAX_SLIPPAGE_BPS),
+            vec![
+                AccountMeta::new_readonly(self.authority.pubkey(), true),
+                AccountMeta::new(self.portfolio, false),
+            ],
+        );
+        let authority = self.authority.insecure_clone();
+        self.env.send(&[ix], &[&authority])
+    }
+
+    /// Swaps `amount_in` of `input_mint` held by the portfolio for `amount_out` of
+    /// `output_mint`.
+    fn rebalance(
+        &mut self,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> TransactionResult {
+        let portfolio = self.portfolio;
+        let route = self.env.route(
+            portfolio,
+            ata(&portfolio, &input_mint),
+            ata(&portfolio, &output_mint),
+            input_mint,
+            output_mint,
+            amount_in,
+            amount_out,
+        );
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.keeper.pubkey(), true),
+            AccountMeta::new_readonly(portfolio, false),
+            AccountMeta::new_readonly(self.env.config, false),
+            AccountMeta::new_readonly(input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(output_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&portfolio, &input_mint), false),
+            AccountMeta::new(ata(&portfolio, &output_mint), false),
+            AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+        ];
+        for mint in [self.mint_a, self.mint_b] {
+            accounts.extend([
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(ata(&portfolio, &mint), false),
+                AccountMeta::new_readonly(self.price_update(&mint), false),
+            ]);
+        }
+        accounts.extend_from_slice(&route.accounts);
+        let ix = instruction("rebalance_portfolio", route.data.clone(), accounts);
+        let keeper = self.keeper.insecure_clone();
+        self.env.send(&[ix], &[&keeper])
+    }
+
+    fn holding(&self, mint: &Pubkey) -> u64 {
+        self.env.balance(&ata(&self.portfolio, mint))
+    }
+}
+
+#[test]
+fn rebalances_towards_targets() {
+    let mut fixture = Fixture::new();
+    let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+    fixture
+        .rebalance(mint_a, mint_b, 1_000_000, 1_000_000)
+        .expect("rebalance_portfolio failed");
+    assert_eq!(fixture.holding(&mint_a), 2_000_000);
+    assert_eq!(fixture.holding(&mint_b), 2_000_000);
+}
+
+#[test]
+fn rejects_rebalance_moving_away_from_targets() {
+    let mut fixture = Fixture::new();
+    let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+    let result = fixture.rebalance(mint_b, mint_a, 500_000, 500_000);
+    assert_error(result, "RebalanceNotImproving");
+}
+
+#[test]
+fn rejects_rate_beyond_max_slippage() {
+    let mut fixture = Fixture::new();
+    let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+    let result = fixture.rebalance(mint_a, mint_b, 1_000_000, 980_000);
+    assert_error(result, "OracleDeviationExceeded");
+}
+
+#[test]
+fn rejects_mint_outside_portfolio() {
+    let mut fixture = Fixture::new();
+    let mint_a = fixture.mint_a;
+    let mint_c = fixture.env.mint();
+    let portfolio = fixture.portfolio;
+    fixture.env.token_account(&portfolio, &mint_c, 0);
+    let result = fixture.rebalance(mint_a, mint_c, 1_000_000, 1_000_000);
+    assert_error(result, "InvalidPortfolioAccounts");
+}
+
+#[test]
+fn rejects_stale_prices() {
+    let mut fixture = Fixture::new();
+    fixture.post_prices(MAX_ORACLE_AGE + 1);
+    let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+    let result = fixture.rebalance(mint_a, mint_b, 1_000_000, 1_000_000);
+    assert_error(result, "StaleOraclePrice");
+}
+
+#[test]
+fn rejects_rebalance_past_new_targets() {
+    let mut fixture = Fixture::new();
+    let (mint_a, mint_b) = (fixture.mint_a, fixture.mint_b);
+    //Three quarters in the first mint is where the portfolio already is
+    fixture
+        .set_targets(vec![
+            PortfolioAsset {
+                mint: mint_a,
+                target_bps: 7_500,
+            },
+            PortfolioAsset {
+                mint: mint_b,
+                target_bps: 2_500,
+            },
+        ])
+        .expect("set_portfolio_targets failed");
+    let result = fixture.rebalance(mint_a, mint_b, 1_000_000, 1_000_000);
+    assert_error(result, "RebalanceNotImproving");
+}
+
+#[test]
+fn rejects_targets_not_adding_up() {
+    let mut fixture = Fixture::new();
+    let mut targets = fixture.even_targets();
+    targets[1].target_bps -= 1;
+    let result = fixture.set_targets(targets);
+    assert_error(result, "InvalidPortfolioTargets");
+
+    let mut targets = fixture.even_targets();
+    targets[1].mint = targets[0].mint;
+    let result = fixture.set_targets(targets);
+    assert_error(result, "InvalidPortfolioTargets");
+}
+
+#[test]
+fn authority_withdraws_holdings() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority.insecure_clone();
+    let mint_b = fixture.mint_b;
+    let destination = fixture.env.token_account(&authority.pubkey(), &mint_b, 0);
+    let ix = instruction(
+        "withdraw_portfolio_token",
+        HOLDING_B,
+        vec![
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new_readonly(fixture.portfolio, false),
+            AccountMeta::new_readonly(mint_b, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&fixture.portfolio, &mint_b), false),
+            AccountMeta::new(destination, false),
+        ],
+    );
+    fixture
+        .env
+        .send(&[ix], &[&authority])
+        .expect("withdraw_portfolio_token failed");
+    assert_eq!(fixture.env.balance(&destination), HOLDING_B);
+    assert_eq!(fixture.holding(&mint_b), 0);
+}
@@ -0,0 +1,153 @@
+//! Per window caps on what `swap` pays out of a vault, for a whole mint and per caller, and the
+//! config authority's per mint and per caller overrides.
+
+mod common;
+
+use {
+    common::{assert_error, instruction, ConfigChange, SwapFixture, TestEnv, SYSTEM_PROGRAM_ID},
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_PAID: u64 = 2_000_000;
+const WINDOW_SLOTS: u64 = 100;
+
+fn set_rate_limits(env: &mut TestEnv, default_mint_cap: u64, default_caller_cap: u64) {
+    env.apply_change(ConfigChange::RateLimits {
+        window_slots: WINDOW_SLOTS,
+        default_mint_cap,
+        default_caller_cap,
+    });
+}
+
+fn swap(env: &mut TestEnv, fixture: &SwapFixture) -> TransactionResult {
+    env.send(&[fixture.instruction(AMOUNT_PAID)], &[&fixture.payer])
+}
+
+/// Queues the mint cap override and applies it once the timelock is over.
+fn set_mint_rate_limit(env: &mut TestEnv, fixture: &SwapFixture, cap: Option<u64>) {
+    let accounts = &fixture.accounts;
+    let pending_change = env.queue_and_wait(ConfigChange::MintRateLimit {
+        vault: accounts.vault,
+        mint: accounts.output_mint,
+        cap,
+    });
+    let authority = env.authority.insecure_clone();
+    let ix = instruction(
+        "set_mint_rate_limit",
+        (accounts.vault, cap),
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(authority.pubkey(), false),
+            AccountMeta::new_readonly(env.config, false),
+            AccountMeta::new_readonly(accounts.output_mint, false),
+            AccountMeta::new(accounts.mint_rate_limit(), false),
+            AccountMeta::new(pending_change, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+    );
+    env.send(&[ix], &[&authority])
+        .expect("set_mint_rate_limit failed");
+}
+
+/// Queues the caller cap override and applies it once the timelock is over.
+fn set_caller_rate_limit(env: &mut TestEnv, fixture: &SwapFixture, cap: Option<u64>) {
+    let accounts = &fixture.accounts;
+    let pending_change = env.queue_and_wait(ConfigChange::CallerRateLimit {
+        vault: accounts.vault,
+        caller: accounts.payer,
+        mint: accounts.output_mint,
+        cap,
+    });
+    let authority = env.authority.insecure_clone();
+    let ix = instruction(
+        "set_caller_rate_limit",
+        (accounts.vault, accounts.payer, cap),
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(authority.pubkey(), false),
+            AccountMeta::new_readonly(env.config, false),
+            AccountMeta::new_readonly(accounts.output_mint, false),
+            AccountMeta::new(accounts.caller_rate_limit(), false),
+            AccountMeta::new(pending_change, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+    );
+    env.send(&[ix], &[&authority])
+        .expect("set_caller_rate_limit failed");
+}
+
+#[test]
+fn caps_mint_output_per_window() {
+    let mut env = TestEnv::new();
+    set_rate_limits(&mut env, 3 * AMOUNT_PAID / 2, u64::MAX);
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_PAID);
+    let result = swap(&mut env, &fixture);
+    assert_error(result, "RateLimitExceeded");
+
+    set_rate_limits(&mut env, 3 * AMOUNT_PAID, u64::MAX);
+    swap(&mut env, &fixture).expect("first swap failed");
+    env.refill_swap(&mut fixture, AMOUNT_IN, AMOUNT_PAID);
+    let result = swap(&mut env, &fixture);
+    assert_error(result, "RateLimitExceeded");
+
+    //The volume starts over in the next window
+    env.warp_slots(WINDOW_SLOTS);
+    swap(&mut env, &fixture).expect("swap in the next window failed");
+}
+
+#[test]
+fn caps_caller_output_per_window() {
+    let mut env = TestEnv::new();
+    set_rate_limits(&mut env, u64::MAX, AMOUNT_PAID);
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_PAID);
+    swap(&mut env, &fixture).expect("first swap failed");
+    env.refill_swap(&mut fixture, AMOUNT_IN, AMOUNT_PAID);
+
+    //Paying a fresh recipient still counts towards the caller's volume
+    let recipient = Pubkey::new_unique();
+    let output_mint = fixture.accounts.output_mint;
+    fixture.accounts.recipient = recipient;
+    let recipient_token_account = env.token_account(&recipient, &output_mint, 0);
+    fixture.accounts.recipient_token_account = Some(recipient_token_account);
+    let result = swap(&mut env, &fixture);
+    assert_error(result, "RateLimitExceeded");
+
+    //Another caller has a volume of its own
+    let payer = env.wallet();
+    fixture.accounts.payer = payer.pubkey();
+    fixture.accounts.surplus_destination = env.token_account(&payer.pubkey(), &output_mint, 0);
+    fixture.payer = payer;
+    swap(&mut env, &fixture).expect("swap by another caller failed");
+}
+
+#[test]
+fn mint_cap_override_replaces_default_cap() {
+    let mut env = TestEnv::new();
+    set_rate_limits(&mut env, AMOUNT_PAID, u64::MAX);
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_PAID);
+    set_mint_rate_limit(&mut env, &fixture, Some(2 * AMOUNT_PAID));
+
+    swap(&mut env, &fixture).expect("first swap failed");
+    env.refill_swap(&mut fixture, AMOUNT_IN, AMOUNT_PAID);
+    swap(&mut env, &fixture).expect("second swap failed");
+    env.refill_swap(&mut fixture, AMOUNT_IN, AMOUNT_PAID);
+    let result = swap(&mut env, &fixture);
+    assert_error(result, "RateLimitExceeded");
+}
+
+#[test]
+fn caller_cap_override_replaces_default_cap() {
+    let mut env = TestEnv::new();
+    set_rate_limits(&mut env, u64::MAX, u64::MAX);
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_PAID);
+    set_caller_rate_limit(&mut env, &fixture, Some(AMOUNT_PAID - 1));
+    let result = swap(&mut env, &fixture);
+    assert_error(result, "RateLimitExceeded");
+
+    set_caller_rate_limit(&mut env, &fixture, None);
+    swap(&mut env, &fixture).expect("swap failed");
+}
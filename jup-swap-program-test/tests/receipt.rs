@@ -0,0 +1,158 @@
+//! Receipts `swap` creates when asked to, and `close_receipt` once they've been kept long enough.
+
+mod common;
+
+use {
+    common::{
+        assert_error, instruction, none, pda, swap_instruction, SwapArgs, SwapFixture, TestEnv,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 2_500_000;
+const AMOUNT_PAID: u64 = 2_000_000;
+const RECEIPT_ID: u64 = 42;
+const RETENTION_PERIOD: i64 = 90 * 24 * 60 * 60;
+
+fn receipt_address(payer: &Pubkey, id: u64) -> Pubkey {
+    pda(&[b"receipt", payer.as_ref(), &id.to_le_bytes()])
+}
+
+/// The fixture's swap, asking for a receipt with `id`.
+fn swap_with_receipt(fixture: &SwapFixture, id: u64) -> Instruction {
+    let mut accounts = fixture.accounts.clone();
+    accounts.receipt = Some(receipt_address(&fixture.payer.pubkey(), id));
+    let mut args = SwapArgs::new(&fixture.route, AMOUNT_PAID);
+    args.receipt_id = Some(id);
+    swap_instruction(&accounts, args, &fixture.route, &[])
+}
+
+fn close_receipt(env: &mut TestEnv, payer: &Keypair, receipt: Pubkey) -> TransactionResult {
+    let ix = instruction(
+        "close_receipt",
+        (),
+        vec![
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(receipt, false),
+        ],
+    );
+    env.send(&[ix], &[payer])
+}
+
+#[test]
+fn records_swap_in_receipt() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    env.send(
+        &[swap_with_receipt(&fixture, RECEIPT_ID)],
+        &[&fixture.payer],
+    )
+    .expect("swap failed");
+
+    let data = env.data(&receipt_address(&fixture.payer.pubkey(), RECEIPT_ID));
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    assert_eq!(&data[..32], fixture.payer.pubkey().as_ref());
+    assert_eq!(read_u64(32), RECEIPT_ID);
+    assert_eq!(&data[40..72], fixture.accounts.input_mint.as_ref());
+    assert_eq!(&data[72..104], fixture.accounts.output_mint.as_ref());
+    assert_eq!(read_u64(104), AMOUNT_IN);
+    assert_eq!(read_u64(112), AMOUNT_OUT);
+    assert_eq!(read_u64(120), AMOUNT_PAID);
+    assert_eq!(&data[128..160], fixture.accounts.recipient.as_ref());
+}
+
+#[test]
+fn rejects_reusing_receipt_id() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    env.send(
+        &[swap_with_receipt(&fixture, RECEIPT_ID)],
+        &[&fixture.payer],
+    )
+    .expect("swap failed");
+    let recipient_token_account = fixture.accounts.recipient_token_account.unwrap();
+
+    env.refill_swap(&mut fixture, AMOUNT_IN, AMOUNT_OUT);
+    let result = env.send(
+        &[swap_with_receipt(&fixture, RECEIPT_ID)],
+        &[&fixture.payer],
+    );
+    assert!(result.is_err());
+    assert_eq!(env.balance(&recipient_token_account), AMOUNT_PAID);
+
+    //The same swap goes through with a new id
+    env.send(
+        &[swap_with_receipt(&fixture, RECEIPT_ID + 1)],
+        &[&fixture.payer],
+    )
+    .expect("swap failed");
+    assert_eq!(env.balance(&recipient_token_account), 2 * AMOUNT_PAID);
+}
+
+#[test]
+fn rejects_receipt_id_without_receipt_account() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let mut ix = swap_with_receipt(&fixture, RECEIPT_ID);
+    //receipt
+    ix.accounts[17] = none();
+    let result = env.send(&[ix], &[&fixture.payer]);
+    assert_error(result, "ReceiptMismatch");
+}
+
+#[test]
+fn closes_receipt_after_retention_period() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    env.send(
+        &[swap_with_receipt(&fixture, RECEIPT_ID)],
+        &[&fixture.payer],
+    )
+    .expect("swap failed");
+    let receipt = receipt_address(&fixture.payer.pubkey(), RECEIPT_ID);
+
+    env.warp_seconds(RETENTION_PERIOD - 1);
+    let result = close_receipt(&mut env, &fixture.payer, receipt);
+    assert_error(result, "RetentionNotElapsed");
+
+    env.warp_seconds(1);
+    close_receipt(&mut env, &fixture.payer, receipt).expect("close_receipt failed");
+    assert!(!env.exists(&receipt));
+}
+
+#[test]
+fn frees_receipt_id_once_closed() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    env.send(
+        &[swap_with_receipt(&fixture, RECEIPT_ID)],
+        &[&fixture.payer],
+    )
+    .expect("swap failed");
+    let receipt = receipt_address(&fixture.payer.pubkey(), RECEIPT_ID);
+    let recipient_token_account = fixture.accounts.recipient_token_account.unwrap();
+
+    //The id stays taken for the whole retention period
+    env.refill_swap(&mut fixture, AMOUNT_IN, AMOUNT_OUT);
+    env.warp_seconds(RETENTION_PERIOD);
+    let result = env.send(
+        &[swap_with_receipt(&fixture, RECEIPT_ID)],
+        &[&fixture.payer],
+    );
+    assert!(result.is_err());
+
+    //Closing the receipt gives the id back, a retry with it pays again
+    close_receipt(&mut env, &fixture.payer, receipt).expect("close_receipt failed");
+    env.send(
+        &[swap_with_receipt(&fixture, RECEIPT_ID)],
+        &[&fixture.payer],
+    )
+    .expect("swap failed");
+    assert_eq!(env.balance(&recipient_token_account), 2 * AMOUNT_PAID);
+    assert!(env.exists(&receipt));
+}
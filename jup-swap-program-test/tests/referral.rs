@@ -0,0 +1,204 @@
+//! Referrers approved by an integrator earning a share of its fee on the swaps they refer, and
+//! `claim_referral` paying it out.
+
+mod common;
+
+use {
+    common::{
+        assert_error, ata, instruction, integrator_vault, pda, ConfigChange, SwapFixture, TestEnv,
+        ATA_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const INTEGRATOR_ID: u64 = 4;
+const FEE_BPS: u16 = 100;
+const REFERRAL_SHARE_BPS: u16 = 2_500;
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_PAID: u64 = 2_000_000;
+const FEE: u64 = AMOUNT_PAID * FEE_BPS as u64 / 10_000;
+const REFERRAL_FEE: u64 = FEE * REFERRAL_SHARE_BPS as u64 / 10_000;
+
+struct Fixture {
+    env: TestEnv,
+    authority: Keypair,
+    integrator: Pubkey,
+    swap: SwapFixture,
+}
+
+impl Fixture {
+    /// An integrator sharing its fee with referrers, and a swap out of its vault.
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let authority = env.wallet();
+        let fee_treasury = Pubkey::new_unique();
+        let integrator =
+            env.create_integrator(INTEGRATOR_ID, authority.pubkey(), FEE_BPS, fee_treasury);
+        env.apply_integrator_change(
+            &authority,
+            integrator,
+            ConfigChange::Integrator {
+                id: INTEGRATOR_ID,
+                authority: authority.pubkey(),
+                fee_bps: FEE_BPS,
+                fee_treasury,
+                referral_share_bps: REFERRAL_SHARE_BPS,
+                allowed_mints: vec![],
+                relayers: vec![],
+            },
+        );
+        let mut swap =
+            env.swap_fixture_in(integrator_vault(&integrator), AMOUNT_IN, AMOUNT_PAID + FEE);
+        let fee_treasury_token_account =
+            env.token_account(&fee_treasury, &swap.accounts.output_mint, 0);
+        swap.accounts.integrator = Some((integrator, fee_treasury_token_account));
+
+        Self {
+            env,
+            authority,
+            integrator,
+            swap,
+        }
+    }
+
+    fn referrer_account(&self, referrer: &Pubkey) -> Pubkey {
+        pda(&[
+            b"referrer",
+            self.integrator.as_ref(),
+            referrer.as_ref(),
+            self.swap.accounts.output_mint.as_ref(),
+        ])
+    }
+
+    fn approve_referrer(&mut self, authority: &Keypair, referrer: Pubkey) -> TransactionResult {
+        let mint = self.swap.accounts.output_mint;
+        let referrer_account = self.referrer_account(&referrer);
+        let ix = instruction(
+            "approve_referrer",
+            (),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(self.integrator, false),
+                AccountMeta::new_readonly(referrer, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new(referrer_account, false),
+                AccountMeta::new(ata(&referrer_account, &mint), false),
+                AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        self.env.send(&[ix], &[authority])
+    }
+
+    /// The swap, referred by `referrer`.
+    fn swap(&mut self, referrer: Pubkey) -> TransactionResult {
+        let referrer_account = self.referrer_account(&referrer);
+        let escrow = ata(&referrer_account, &self.swap.accounts.output_mint);
+        self.swap.accounts.referrer = Some((referrer, referrer_account, escrow));
+        let ix = self.swap.instruction(AMOUNT_PAID);
+        self.env.send(&[ix], &[&self.swap.payer])
+    }
+
+    fn claim_referral(&mut self, referrer: &Keypair) -> TransactionResult {
+        let mint = self.swap.accounts.output_mint;
+        let referrer_account = self.referrer_account(&referrer.pubkey());
+        let ix = instruction(
+            "claim_referral",
+            (),
+            vec![
+                AccountMeta::new(referrer.pubkey(), true),
+                AccountMeta::new(referrer_account, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new(ata(&referrer_account, &mint), false),
+                AccountMeta::new(ata(&referrer.pubkey(), &mint), false),
+                AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        self.env.send(&[ix], &[referrer])
+    }
+
+    /// Unclaimed and total earnings of `referrer`.
+    fn earnings(&self, referrer: &Pubkey) -> (u64, u64) {
+        let data = self.env.data(&self.referrer_account(referrer));
+        (
+            u64::from_le_bytes(data[96..104].try_into().unwrap()),
+            u64::from_le_bytes(data[104..112].try_into().unwrap()),
+        )
+    }
+}
+
+#[test]
+fn shares_fee_with_approved_referrer() {
+    let mut fixture = Fixture::new();
+    let referrer = fixture.env.wallet();
+    let authority = fixture.authority.insecure_clone();
+    fixture
+        .approve_referrer(&authority, referrer.pubkey())
+        .expect("approve_referrer failed");
+    fixture.swap(referrer.pubkey()).expect("swap failed");
+
+    let accounts = &fixture.swap.accounts;
+    let (_, fee_treasury_token_account) = accounts.integrator.unwrap();
+    let (_, _, escrow) = accounts.referrer.unwrap();
+    assert_eq!(
+        fixture
+            .env
+            .balance(&accounts.recipient_token_account.unwrap()),
+        AMOUNT_PAID
+    );
+    assert_eq!(fixture.env.balance(&escrow), REFERRAL_FEE);
+    assert_eq!(
+        fixture.env.balance(&fee_treasury_token_account),
+        FEE - REFERRAL_FEE
+    );
+    assert_eq!(
+        fixture.earnings(&referrer.pubkey()),
+        (REFERRAL_FEE, REFERRAL_FEE)
+    );
+
+    fixture
+        .claim_referral(&referrer)
+        .expect("claim_referral failed");
+    let output_mint = fixture.swap.accounts.output_mint;
+    assert_eq!(
+        fixture.env.balance(&ata(&referrer.pubkey(), &output_mint)),
+        REFERRAL_FEE
+    );
+    assert_eq!(fixture.earnings(&referrer.pubkey()), (0, REFERRAL_FEE));
+    let result = fixture.claim_referral(&referrer);
+    assert_error(result, "NothingToClaim");
+}
+
+#[test]
+fn rejects_payer_referring_itself() {
+    let mut fixture = Fixture::new();
+    let payer = fixture.swap.payer.pubkey();
+    let authority = fixture.authority.insecure_clone();
+    fixture
+        .approve_referrer(&authority, payer)
+        .expect("approve_referrer failed");
+    let result = fixture.swap(payer);
+    assert_error(result, "SelfReferral");
+}
+
+#[test]
+fn rejects_referrer_not_approved() {
+    let mut fixture = Fixture::new();
+    let result = fixture.swap(Pubkey::new_unique());
+    assert_error(result, "AccountNotInitialized");
+}
+
+#[test]
+fn rejects_approval_by_others_than_integrator_authority() {
+    let mut fixture = Fixture::new();
+    let stranger = fixture.env.wallet();
+    let result = fixture.approve_referrer(&stranger, stranger.pubkey());
+    assert_error(result, "Unauthorized");
+}
@@ -0,0 +1,73 @@
+//! `swap` returning what it did through `set_return_data`.
+
+mod common;
+
+use {
+    borsh::BorshDeserialize,
+    common::{assert_error, SwapResult, TestEnv, CPI_SWAP_PROGRAM_ID},
+    solana_pubkey::Pubkey,
+};
+
+#[test]
+fn returns_swap_result() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(1_000_000, 2_500_000);
+    let meta = env
+        .send(&[fixture.instruction(2_000_000)], &[&fixture.payer])
+        .expect("swap failed");
+
+    assert_eq!(meta.return_data.program_id, CPI_SWAP_PROGRAM_ID);
+    assert_eq!(
+        SwapResult::try_from_slice(&meta.return_data.data).unwrap(),
+        SwapResult {
+            amount_in: 1_000_000,
+            amount_out: 2_500_000,
+            amount_paid: 2_000_000,
+            fee: 0,
+        }
+    );
+}
+
+#[test]
+fn returns_measured_amounts_rather_than_vault_balances() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(1_000_000, 2_000_000);
+    //Balances already in the vault are no part of the swap
+    let vault_input = fixture.accounts.vault_input_token_account();
+    let vault_output = fixture.accounts.vault_output_token_account();
+    let (input_mint, output_mint, vault) = (
+        fixture.accounts.input_mint,
+        fixture.accounts.output_mint,
+        fixture.accounts.vault,
+    );
+    env.set_token_account(vault_input, input_mint, vault, 1_500_000);
+    env.set_token_account(vault_output, output_mint, vault, 700_000);
+
+    let meta = env
+        .send(&[fixture.instruction(2_000_000)], &[&fixture.payer])
+        .expect("swap failed");
+    assert_eq!(
+        SwapResult::try_from_slice(&meta.return_data.data).unwrap(),
+        SwapResult {
+            amount_in: 1_000_000,
+            amount_out: 2_000_000,
+            amount_paid: 2_000_000,
+            fee: 0,
+        }
+    );
+    assert_eq!(env.balance(&vault_input), 500_000);
+    assert_eq!(env.balance(&vault_output), 700_000);
+}
+
+#[test]
+fn returns_nothing_when_the_swap_fails() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(1_000_000, 2_000_000);
+    let output_mint = fixture.accounts.output_mint;
+    fixture.accounts.surplus_destination =
+        env.token_account(&Pubkey::new_unique(), &output_mint, 0);
+    let result = env.send(&[fixture.instruction(1_900_000)], &[&fixture.payer]);
+    let failure = result.as_ref().unwrap_err();
+    assert!(failure.meta.return_data.data.is_empty());
+    assert_error(result, "InvalidSurplusDestination");
+}
@@ -0,0 +1,97 @@
+//! `swap` sweeping whatever the route delivered beyond the payout to the payer, or to the
+//! config's surplus treasury, instead of leaving it in the vault.
+
+mod common;
+
+use {
+    common::{assert_error, ConfigChange, SwapFixture, TestEnv},
+    litesvm::types::TransactionResult,
+    solana_pubkey::Pubkey,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 2_000_000;
+const SURPLUS: u64 = 50_000;
+const AMOUNT_PAID: u64 = AMOUNT_OUT - SURPLUS;
+
+fn swap(env: &mut TestEnv, fixture: &SwapFixture) -> TransactionResult {
+    env.send(&[fixture.instruction(AMOUNT_PAID)], &[&fixture.payer])
+}
+
+#[test]
+fn sweeps_surplus_to_payer() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    swap(&mut env, &fixture).expect("swap failed");
+
+    let accounts = &fixture.accounts;
+    let recipient_token_account = accounts.recipient_token_account.unwrap();
+    assert_eq!(env.balance(&recipient_token_account), AMOUNT_PAID);
+    assert_eq!(env.balance(&accounts.surplus_destination), SURPLUS);
+    assert_eq!(env.balance(&accounts.vault_output_token_account()), 0);
+}
+
+#[test]
+fn sweeps_surplus_to_treasury() {
+    let mut env = TestEnv::new();
+    let treasury = Pubkey::new_unique();
+    env.apply_change(ConfigChange::SurplusTreasury {
+        surplus_treasury: Some(treasury),
+    });
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let output_mint = fixture.accounts.output_mint;
+
+    //The payer no longer gets the surplus
+    let result = swap(&mut env, &fixture);
+    assert_error(result, "InvalidSurplusDestination");
+
+    let treasury_token_account = env.token_account(&treasury, &output_mint, 0);
+    fixture.accounts.surplus_destination = treasury_token_account;
+    swap(&mut env, &fixture).expect("swap failed");
+    assert_eq!(env.balance(&treasury_token_account), SURPLUS);
+    assert_eq!(
+        env.balance(&fixture.accounts.vault_output_token_account()),
+        0
+    );
+}
+
+#[test]
+fn rejects_surplus_destination_of_others() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let output_mint = fixture.accounts.output_mint;
+    fixture.accounts.surplus_destination =
+        env.token_account(&Pubkey::new_unique(), &output_mint, 0);
+    let result = swap(&mut env, &fixture);
+    assert_error(result, "InvalidSurplusDestination");
+}
+
+#[test]
+fn rejects_surplus_destination_of_other_mint() {
+    let mut env = TestEnv::new();
+    let mut fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let (payer, input_mint) = (fixture.accounts.payer, fixture.accounts.input_mint);
+    fixture.accounts.surplus_destination = env.token_account(&payer, &input_mint, 0);
+    let result = swap(&mut env, &fixture);
+    assert_error(result, "InvalidSurplusDestination");
+}
+
+#[test]
+fn tops_up_payout_from_vault_balance() {
+    let mut env = TestEnv::new();
+    let fixture = env.swap_fixture(AMOUNT_IN, AMOUNT_OUT);
+    let vault_output = fixture.accounts.vault_output_token_account();
+
+    //Nothing in the vault to make up the difference
+    let result = env.send(&[fixture.instruction(AMOUNT_OUT + 1)], &[&fixture.payer]);
+    assert!(result.is_err());
+
+    let (output_mint, vault) = (fixture.accounts.output_mint, fixture.accounts.vault);
+    env.set_token_account(vault_output, output_mint, vault, 100);
+    env.send(&[fixture.instruction(AMOUNT_OUT + 100)], &[&fixture.payer])
+        .expect("swap failed");
+    let recipient_token_account = fixture.accounts.recipient_token_account.unwrap();
+    assert_eq!(env.balance(&recipient_token_account), AMOUNT_OUT + 100);
+    assert_eq!(env.balance(&fixture.accounts.surplus_destination), 0);
+    assert_eq!(env.balance(&vault_output), 0);
+}
@@ -0,0 +1,227 @@
+//! Config changes going through the timelock: queued by the authority, executable once the
+//! delay is over and cancellable by the guardian in the meantime.
+
+mod common;
+
+use {
+    common::{
+        assert_error, instruction, mint_rate_limit_address, shared_vault, ConfigChange, TestEnv,
+        DEFAULT_TIMELOCK_DELAY, SYSTEM_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+fn cancel_change(
+    env: &mut TestEnv,
+    signer: &Keypair,
+    proposer: Pubkey,
+    pending_change: Pubkey,
+) -> TransactionResult {
+    let ix = instruction(
+        "cancel_config_change",
+        (),
+        vec![
+            AccountMeta::new_readonly(signer.pubkey(), true),
+            AccountMeta::new(proposer, false),
+            AccountMeta::new_readonly(env.config, false),
+            AccountMeta::new(pending_change, false),
+        ],
+    );
+    env.send(&[ix], &[signer])
+}
+
+fn set_mint_rate_limit(
+    env: &mut TestEnv,
+    mint: Pubkey,
+    cap: Option<u64>,
+    pending_change: Pubkey,
+) -> TransactionResult {
+    let authority = env.authority.insecure_clone();
+    let ix = instruction(
+        "set_mint_rate_limit",
+        (shared_vault(), cap),
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(authority.pubkey(), false),
+            AccountMeta::new_readonly(env.config, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(mint_rate_limit_address(&shared_vault(), &mint), false),
+            AccountMeta::new(pending_change, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+    );
+    env.send(&[ix], &[&authority])
+}
+
+#[test]
+fn executes_change_once_delay_is_over() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    let (pending_change, result) =
+        env.queue_change(&authority, None, ConfigChange::TimelockDelay { delay: 60 });
+    result.expect("queue_config_change failed");
+
+    env.warp_seconds(DEFAULT_TIMELOCK_DELAY - 1);
+    let result = env.execute_change(&authority.pubkey(), None, pending_change);
+    assert_error(result, "TimelockNotElapsed");
+
+    env.warp_seconds(1);
+    env.execute_change(&authority.pubkey(), None, pending_change)
+        .expect("execute_config_change failed");
+    assert!(!env.exists(&pending_change));
+
+    //Later changes wait out the new delay
+    let (pending_change, result) =
+        env.queue_change(&authority, None, ConfigChange::TimelockDelay { delay: 0 });
+    result.expect("queue_config_change failed");
+    env.warp_seconds(60);
+    env.execute_change(&authority.pubkey(), None, pending_change)
+        .expect("execute_config_change failed");
+}
+
+#[test]
+fn guardian_cancels_pending_change() {
+    let mut env = TestEnv::new();
+    let guardian = env.wallet();
+    env.apply_change(ConfigChange::Guardian {
+        guardian: guardian.pubkey(),
+    });
+    let authority = env.authority.insecure_clone();
+    let (pending_change, result) = env.queue_change(
+        &authority,
+        None,
+        ConfigChange::Authority {
+            authority: Pubkey::new_unique(),
+        },
+    );
+    result.expect("queue_config_change failed");
+
+    cancel_change(&mut env, &guardian, authority.pubkey(), pending_change)
+        .expect("cancel_config_change failed");
+    assert!(!env.exists(&pending_change));
+    env.warp_seconds(DEFAULT_TIMELOCK_DELAY);
+    let result = env.execute_change(&authority.pubkey(), None, pending_change);
+    assert!(result.is_err());
+}
+
+#[test]
+fn proposer_withdraws_own_change() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    let (pending_change, result) =
+        env.queue_change(&authority, None, ConfigChange::TimelockDelay { delay: 0 });
+    result.expect("queue_config_change failed");
+    cancel_change(&mut env, &authority, authority.pubkey(), pending_change)
+        .expect("cancel_config_change failed");
+    assert!(!env.exists(&pending_change));
+}
+
+#[test]
+fn rejects_cancel_by_others() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    let (pending_change, result) =
+        env.queue_change(&authority, None, ConfigChange::TimelockDelay { delay: 0 });
+    result.expect("queue_config_change failed");
+    let stranger = env.wallet();
+    let result = cancel_change(&mut env, &stranger, authority.pubkey(), pending_change);
+    assert_error(result, "Unauthorized");
+}
+
+#[test]
+fn rejects_change_queued_by_others() {
+    let mut env = TestEnv::new();
+    let stranger = env.wallet();
+    let (_, result) = env.queue_change(
+        &stranger,
+        None,
+        ConfigChange::Authority {
+            authority: stranger.pubkey(),
+        },
+    );
+    assert_error(result, "Unauthorized");
+}
+
+#[test]
+fn rejects_invalid_change_when_queued() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    let (_, result) = env.queue_change(&authority, None, ConfigChange::TimelockDelay { delay: -1 });
+    assert_error(result, "InvalidInterval");
+}
+
+#[test]
+fn rejects_change_once_proposer_lost_authority() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    let (handover, result) = env.queue_change(
+        &authority,
+        None,
+        ConfigChange::Authority {
+            authority: Pubkey::new_unique(),
+        },
+    );
+    result.expect("queue_config_change failed");
+    let (pending_change, result) =
+        env.queue_change(&authority, None, ConfigChange::TimelockDelay { delay: 0 });
+    result.expect("queue_config_change failed");
+
+    env.warp_seconds(DEFAULT_TIMELOCK_DELAY);
+    env.execute_change(&authority.pubkey(), None, handover)
+        .expect("execute_config_change failed");
+    let result = env.execute_change(&authority.pubkey(), None, pending_change);
+    assert_error(result, "Unauthorized");
+}
+
+#[test]
+fn rejects_rate_limit_change_through_execute_config_change() {
+    let mut env = TestEnv::new();
+    let mint = env.mint();
+    let pending_change = env.queue_and_wait(ConfigChange::MintRateLimit {
+        vault: shared_vault(),
+        mint,
+        cap: Some(1),
+    });
+    let authority = env.authority.pubkey();
+    let result = env.execute_change(&authority, None, pending_change);
+    assert_error(result, "PendingChangeMismatch");
+}
+
+#[test]
+fn rejects_rate_limit_other_than_queued() {
+    let mut env = TestEnv::new();
+    let mint = env.mint();
+    let pending_change = env.queue_and_wait(ConfigChange::MintRateLimit {
+        vault: shared_vault(),
+        mint,
+        cap: Some(1),
+    });
+    let result = set_mint_rate_limit(&mut env, mint, Some(2), pending_change);
+    assert_error(result, "PendingChangeMismatch");
+
+    set_mint_rate_limit(&mut env, mint, Some(1), pending_change)
+        .expect("set_mint_rate_limit failed");
+}
+
+#[test]
+fn rejects_rate_limit_before_delay_is_over() {
+    let mut env = TestEnv::new();
+    let mint = env.mint();
+    let authority = env.authority.insecure_clone();
+    let (pending_change, result) = env.queue_change(
+        &authority,
+        None,
+        ConfigChange::MintRateLimit {
+            vault: shared_vault(),
+            mint,
+            cap: Some(1),
+        },
+    );
+    result.expect("queue_config_change failed");
+    let result = set_mint_rate_limit(&mut env, mint, Some(1), pending_change);
+    assert_error(result, "TimelockNotElapsed");
+}
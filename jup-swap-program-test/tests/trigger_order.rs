@@ -0,0 +1,244 @@
+//! `create_trigger_order` and `trigger_order`: stop-loss / take-profit swaps keepers fill once
+//! a Pyth price crosses one of the order's trigger prices.
+
+mod common;
+
+use {
+    borsh::BorshSerialize,
+    common::{
+        assert_error, ata, instruction, mint_rate_limit_address, pda, shared_vault, TestEnv,
+        ATA_PROGRAM_ID, JUPITER_V6_AGG_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const ORDER_ID: u64 = 8;
+const AMOUNT_IN: u64 = 1_000_000;
+const MIN_AMOUNT_OUT: u64 = 2_000_000;
+const FEED_ID: [u8; 32] = [3; 32];
+// Stop-loss at $90 and take-profit at $120
+const STOP_LOSS_PRICE: i64 = 90;
+const TAKE_PROFIT_PRICE: i64 = 120;
+const PRICE_EXPONENT: i32 = -2;
+const MAX_ORACLE_AGE: i64 = 60;
+
+/// Mirror of the program's `Trigger`.
+#[derive(BorshSerialize, Clone, Copy)]
+struct Trigger {
+    feed_id: [u8; 32],
+    stop_loss_price: Option<i64>,
+    take_profit_price: Option<i64>,
+    exponent: i32,
+}
+
+const TRIGGER: Trigger = Trigger {
+    feed_id: FEED_ID,
+    stop_loss_price: Some(STOP_LOSS_PRICE),
+    take_profit_price: Some(TAKE_PROFIT_PRICE),
+    exponent: 0,
+};
+
+struct Fixture {
+    env: TestEnv,
+    owner: Keypair,
+    keeper: Keypair,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    order: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let owner = env.wallet();
+        let keeper = env.wallet();
+        let input_mint = env.mint();
+        let output_mint = env.mint();
+        env.token_account(&owner.pubkey(), &input_mint, AMOUNT_IN);
+        env.token_account(&owner.pubkey(), &output_mint, 0);
+        let vault = shared_vault();
+        env.token_account(&vault, &input_mint, 0);
+        env.token_account(&vault, &output_mint, 0);
+        let order = pda(&[
+            b"trigger_order",
+            owner.pubkey().as_ref(),
+            &ORDER_ID.to_le_bytes(),
+        ]);
+
+        Self {
+            env,
+            owner,
+            keeper,
+            input_mint,
+            output_mint,
+            order,
+        }
+    }
+
+    fn create_order(&mut self, trigger: Trigger) -> TransactionResult {
+        let owner = self.owner.pubkey();
+        let ix = instruction(
+            "create_trigger_order",
+            (ORDER_ID, AMOUNT_IN, MIN_AMOUNT_OUT, trigger),
+            vec![
+                AccountMeta::new(owner, true),
+                AccountMeta::new(self.order, false),
+                AccountMeta::new_readonly(self.input_mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(self.output_mint, false),
+                AccountMeta::new(ata(&owner, &self.input_mint), false),
+                AccountMeta::new(ata(&self.order, &self.input_mint), false),
+                AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        let owner = self.owner.insecure_clone();
+        self.env.send(&[ix], &[&owner])
+    }
+
+    /// Creates the order with both trigger prices.
+    fn with_order() -> Self {
+        let mut fixture = Self::new();
+        fixture
+            .create_order(TRIGGER)
+            .expect("create_trigger_order failed");
+        fixture
+    }
+
+    /// Fills the order through a route paying `amount_out`, with a price update of `feed_id`
+    /// at `price` cents published `age` seconds ago.
+    fn trigger(
+        &mut self,
+        feed_id: [u8; 32],
+        price: i64,
+        age: i64,
+        amount_out: u64,
+    ) -> TransactionResult {
+        let price_update = Pubkey::new_unique();
+        let publish_time = self.env.now() - age;
+        self.env
+            .set_price_update(price_update, feed_id, price, PRICE_EXPONENT, publish_time);
+        let vault = shared_vault();
+        let owner = self.owner.pubkey();
+        let route = self.env.route(
+            vault,
+            ata(&vault, &self.input_mint),
+            ata(&vault, &self.output_mint),
+            self.input_mint,
+            self.output_mint,
+            AMOUNT_IN,
+            amount_out,
+        );
+        let mut accounts = vec![
+            AccountMeta::new(self.keeper.pubkey(), true),
+            AccountMeta::new(owner, false),
+            AccountMeta::new(self.order, false),
+            AccountMeta::new_readonly(self.env.config, false),
+            AccountMeta::new_readonly(price_update, false),
+            AccountMeta::new_readonly(self.input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.output_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&self.order, &self.input_mint), false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(ata(&vault, &self.input_mint), false),
+            AccountMeta::new(ata(&vault, &self.output_mint), false),
+            AccountMeta::new(ata(&owner, &self.output_mint), false),
+            AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+            AccountMeta::new(mint_rate_limit_address(&vault, &self.output_mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+        accounts.extend_from_slice(&route.accounts);
+        let ix = instruction("trigger_order", route.data.clone(), accounts);
+        let keeper = self.keeper.insecure_clone();
+        self.env.send(&[ix], &[&keeper])
+    }
+
+    fn owner_output(&self) -> u64 {
+        self.env
+            .balance(&ata(&self.owner.pubkey(), &self.output_mint))
+    }
+}
+
+#[test]
+fn stops_loss_when_price_falls_to_stop_loss() {
+    let mut fixture = Fixture::with_order();
+    fixture
+        .trigger(FEED_ID, STOP_LOSS_PRICE * 100, 0, MIN_AMOUNT_OUT)
+        .expect("trigger_order failed");
+    assert_eq!(fixture.owner_output(), MIN_AMOUNT_OUT);
+    assert!(!fixture.env.exists(&fixture.order));
+    assert!(!fixture
+        .env
+        .exists(&ata(&fixture.order, &fixture.input_mint)));
+}
+
+#[test]
+fn takes_profit_when_price_rises_to_take_profit() {
+    let mut fixture = Fixture::with_order();
+    fixture
+        .trigger(FEED_ID, TAKE_PROFIT_PRICE * 100 + 1, 0, MIN_AMOUNT_OUT + 1)
+        .expect("trigger_order failed");
+    assert_eq!(fixture.owner_output(), MIN_AMOUNT_OUT + 1);
+}
+
+#[test]
+fn rejects_price_between_triggers() {
+    let mut fixture = Fixture::with_order();
+    let result = fixture.trigger(FEED_ID, STOP_LOSS_PRICE * 100 + 1, 0, MIN_AMOUNT_OUT);
+    assert_error(result, "TriggerNotMet");
+    let result = fixture.trigger(FEED_ID, TAKE_PROFIT_PRICE * 100 - 1, 0, MIN_AMOUNT_OUT);
+    assert_error(result, "TriggerNotMet");
+}
+
+#[test]
+fn rejects_stale_price() {
+    let mut fixture = Fixture::with_order();
+    let result = fixture.trigger(
+        FEED_ID,
+        STOP_LOSS_PRICE * 100,
+        MAX_ORACLE_AGE + 1,
+        MIN_AMOUNT_OUT,
+    );
+    assert_error(result, "StaleOraclePrice");
+}
+
+#[test]
+fn rejects_price_of_another_feed() {
+    let mut fixture = Fixture::with_order();
+    let result = fixture.trigger([4; 32], STOP_LOSS_PRICE * 100, 0, MIN_AMOUNT_OUT);
+    assert_error(result, "OracleFeedMismatch");
+}
+
+#[test]
+fn rejects_route_below_minimum_out() {
+    let mut fixture = Fixture::with_order();
+    let result = fixture.trigger(FEED_ID, STOP_LOSS_PRICE * 100, 0, MIN_AMOUNT_OUT - 1);
+    assert_error(result, "SlippageExceeded");
+}
+
+#[test]
+fn rejects_order_without_trigger_price() {
+    let mut fixture = Fixture::new();
+    let result = fixture.create_order(Trigger {
+        stop_loss_price: None,
+        take_profit_price: None,
+        ..TRIGGER
+    });
+    assert_error(result, "InvalidTrigger");
+}
+
+#[test]
+fn rejects_stop_loss_at_or_above_take_profit() {
+    let mut fixture = Fixture::new();
+    let result = fixture.create_order(Trigger {
+        stop_loss_price: Some(TAKE_PROFIT_PRICE),
+        ..TRIGGER
+    });
+    assert_error(result, "InvalidTrigger");
+}
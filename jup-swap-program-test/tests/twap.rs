@@ -0,0 +1,221 @@
+//! `create_twap` and `fill_twap_slice`: large orders split into slices, their average price held
+//! to the reference price set at creation.
+
+mod common;
+
+use borsh::BorshSerialize;
+use {
+    common::{
+        assert_error, ata, instruction, mint_rate_limit_address, pda, shared_vault, TestEnv,
+        ATA_PROGRAM_ID, JUPITER_V6_AGG_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const TWAP_ID: u64 = 5;
+const NUM_SLICES: u32 = 3;
+const BASE_SLICE: u64 = 1_000_000;
+// The last slice takes the rounding remainder
+const TOTAL_INPUT: u64 = 3 * BASE_SLICE + 1;
+const MIN_SLOT_GAP: u64 = 10;
+const MIN_OUT_PER_SLICE: u64 = 1_800_000;
+const REFERENCE_OUT_PER_SLICE: u64 = 2_000_000;
+const MAX_DEVIATION_BPS: u16 = 500;
+
+#[derive(BorshSerialize)]
+struct TwapLimits {
+    min_out_per_slice: u64,
+    reference_out_per_slice: u64,
+    max_deviation_bps: u16,
+}
+
+struct Fixture {
+    env: TestEnv,
+    keeper: Keypair,
+    recipient: Pubkey,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    twap: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let owner = env.wallet();
+        let keeper = env.wallet();
+        let recipient = Pubkey::new_unique();
+        let input_mint = env.mint();
+        let output_mint = env.mint();
+        env.token_account(&owner.pubkey(), &input_mint, TOTAL_INPUT);
+        env.token_account(&recipient, &output_mint, 0);
+        let vault = shared_vault();
+        env.token_account(&vault, &input_mint, 0);
+        env.token_account(&vault, &output_mint, 0);
+
+        let twap = pda(&[b"twap", owner.pubkey().as_ref(), &TWAP_ID.to_le_bytes()]);
+        let limits = TwapLimits {
+            min_out_per_slice: MIN_OUT_PER_SLICE,
+            reference_out_per_slice: REFERENCE_OUT_PER_SLICE,
+            max_deviation_bps: MAX_DEVIATION_BPS,
+        };
+        let ix = instruction(
+            "create_twap",
+            (TWAP_ID, TOTAL_INPUT, NUM_SLICES, MIN_SLOT_GAP, limits),
+            vec![
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new_readonly(recipient, false),
+                AccountMeta::new(twap, false),
+                AccountMeta::new_readonly(input_mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(output_mint, false),
+                AccountMeta::new(ata(&owner.pubkey(), &input_mint), false),
+                AccountMeta::new(ata(&twap, &input_mint), false),
+                AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        env.send(&[ix], &[&owner]).expect("create_twap failed");
+
+        Self {
+            env,
+            keeper,
+            recipient,
+            input_mint,
+            output_mint,
+            twap,
+        }
+    }
+
+    fn fill_slice(&mut self, amount_in: u64, amount_out: u64) -> TransactionResult {
+        let vault = shared_vault();
+        let route = self.env.route(
+            vault,
+            ata(&vault, &self.input_mint),
+            ata(&vault, &self.output_mint),
+            self.input_mint,
+            self.output_mint,
+            amount_in,
+            amount_out,
+        );
+        let mut accounts = vec![
+            AccountMeta::new(self.keeper.pubkey(), true),
+            AccountMeta::new(self.twap, false),
+            AccountMeta::new_readonly(self.input_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.output_mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(ata(&self.twap, &self.input_mint), false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(ata(&vault, &self.input_mint), false),
+            AccountMeta::new(ata(&vault, &self.output_mint), false),
+            AccountMeta::new(ata(&self.recipient, &self.output_mint), false),
+            AccountMeta::new_readonly(self.recipient, false),
+            AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.env.config, false),
+            AccountMeta::new_readonly(JUPITER_V6_AGG_PROGRAM_ID, false),
+            AccountMeta::new(mint_rate_limit_address(&vault, &self.output_mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+        accounts.extend_from_slice(&route.accounts);
+        let ix = instruction("fill_twap_slice", route.data.clone(), accounts);
+        let keeper = self.keeper.insecure_clone();
+        self.env.send(&[ix], &[&keeper])
+    }
+
+    /// Slices filled, input filled and output filled so far.
+    fn progress(&self) -> (u32, u64, u64) {
+        let data = self.env.data(&self.twap);
+        (
+            u32::from_le_bytes(data[148..152].try_into().unwrap()),
+            u64::from_le_bytes(data[168..176].try_into().unwrap()),
+            u64::from_le_bytes(data[176..184].try_into().unwrap()),
+        )
+    }
+}
+
+#[test]
+fn fills_every_slice_at_the_reference_price() {
+    let mut fixture = Fixture::new();
+    fixture
+        .fill_slice(BASE_SLICE, REFERENCE_OUT_PER_SLICE)
+        .expect("first slice failed");
+    fixture.env.warp_slots(MIN_SLOT_GAP);
+    //Within the allowed deviation below the reference price
+    let low_out = REFERENCE_OUT_PER_SLICE * 95 / 100;
+    fixture
+        .fill_slice(BASE_SLICE, low_out)
+        .expect("second slice failed");
+    fixture.env.warp_slots(MIN_SLOT_GAP);
+    fixture
+        .fill_slice(BASE_SLICE + 1, REFERENCE_OUT_PER_SLICE + 2)
+        .expect("last slice failed");
+
+    let output = 2 * REFERENCE_OUT_PER_SLICE + 2 + low_out;
+    assert_eq!(fixture.progress(), (NUM_SLICES, TOTAL_INPUT, output));
+    assert_eq!(
+        fixture
+            .env
+            .balance(&ata(&fixture.recipient, &fixture.output_mint)),
+        output
+    );
+
+    fixture.env.warp_slots(MIN_SLOT_GAP);
+    let result = fixture.fill_slice(BASE_SLICE, REFERENCE_OUT_PER_SLICE);
+    assert_error(result, "OrderFilled");
+}
+
+#[test]
+fn rejects_slice_before_slot_gap() {
+    let mut fixture = Fixture::new();
+    fixture
+        .fill_slice(BASE_SLICE, REFERENCE_OUT_PER_SLICE)
+        .expect("first slice failed");
+    fixture.env.warp_slots(MIN_SLOT_GAP - 1);
+    let result = fixture.fill_slice(BASE_SLICE, REFERENCE_OUT_PER_SLICE);
+    assert_error(result, "SlotGapNotElapsed");
+}
+
+#[test]
+fn rejects_first_slice_off_the_reference_price() {
+    let mut fixture = Fixture::new();
+    //Above the minimum out but more than the allowed deviation below the reference
+    let result = fixture.fill_slice(BASE_SLICE, 1_850_000);
+    assert_error(result, "PriceDeviationExceeded");
+    assert_eq!(fixture.progress(), (0, 0, 0));
+}
+
+#[test]
+fn holds_average_price_across_slices() {
+    let mut fixture = Fixture::new();
+    //Right at the allowed deviation
+    let low_out = REFERENCE_OUT_PER_SLICE * 95 / 100;
+    fixture
+        .fill_slice(BASE_SLICE, low_out)
+        .expect("first slice failed");
+    fixture.env.warp_slots(MIN_SLOT_GAP);
+    //Above the minimum out, but it would take the average below the allowed deviation
+    let result = fixture.fill_slice(BASE_SLICE, 1_850_000);
+    assert_error(result, "PriceDeviationExceeded");
+    fixture
+        .fill_slice(BASE_SLICE, REFERENCE_OUT_PER_SLICE)
+        .expect("second slice failed");
+    fixture.env.warp_slots(MIN_SLOT_GAP);
+    //The same price is fine once the average has room for it
+    fixture
+        .fill_slice(BASE_SLICE + 1, 1_850_000)
+        .expect("last slice failed");
+
+    let output = low_out + REFERENCE_OUT_PER_SLICE + 1_850_000;
+    assert_eq!(fixture.progress(), (NUM_SLICES, TOTAL_INPUT, output));
+}
+
+#[test]
+fn rejects_slice_below_minimum_out() {
+    let mut fixture = Fixture::new();
+    let result = fixture.fill_slice(BASE_SLICE, MIN_OUT_PER_SLICE - 1);
+    assert_error(result, "SlippageExceeded");
+}
@@ -0,0 +1,98 @@
+//! `withdraw_token` and `withdraw_lamports`: the config authority sweeping the vault.
+
+mod common;
+
+use {
+    common::{
+        assert_error, ata, instruction, shared_vault, TestEnv, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const VAULT_LAMPORTS: u64 = 10_000_000;
+
+fn withdraw_lamports(env: &mut TestEnv, authority: &Keypair, amount: u64) -> TransactionResult {
+    let ix = instruction(
+        "withdraw_lamports",
+        amount,
+        vec![
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new_readonly(env.config, false),
+            AccountMeta::new(shared_vault(), false),
+            AccountMeta::new(authority.pubkey(), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+    );
+    env.send(&[ix], &[authority])
+}
+
+fn withdraw_token(
+    env: &mut TestEnv,
+    authority: &Keypair,
+    mint: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> TransactionResult {
+    let ix = instruction(
+        "withdraw_token",
+        amount,
+        vec![
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new_readonly(env.config, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(shared_vault(), false),
+            AccountMeta::new(ata(&shared_vault(), &mint), false),
+            AccountMeta::new(destination, false),
+        ],
+    );
+    env.send(&[ix], &[authority])
+}
+
+#[test]
+fn authority_sweeps_vault_tokens() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    let mint = env.mint();
+    env.token_account(&shared_vault(), &mint, 1_000);
+    let destination = env.token_account(&authority.pubkey(), &mint, 0);
+
+    withdraw_token(&mut env, &authority, mint, destination, 400).expect("withdraw_token failed");
+    assert_eq!(env.balance(&destination), 400);
+    assert_eq!(env.balance(&ata(&shared_vault(), &mint)), 600);
+
+    let stranger = env.wallet();
+    let result = withdraw_token(&mut env, &stranger, mint, destination, 600);
+    assert_error(result, "Unauthorized");
+}
+
+#[test]
+fn authority_withdraws_lamports_leaving_vault_rent_exempt_or_empty() {
+    let mut env = TestEnv::new();
+    let authority = env.authority.insecure_clone();
+    env.svm.airdrop(&shared_vault(), VAULT_LAMPORTS).unwrap();
+
+    let result = withdraw_lamports(&mut env, &authority, VAULT_LAMPORTS - 1);
+    assert_error(result, "VaultBelowRentExemption");
+
+    let rent = env.svm.minimum_balance_for_rent_exemption(0);
+    withdraw_lamports(&mut env, &authority, VAULT_LAMPORTS - rent)
+        .expect("withdraw_lamports failed");
+    assert_eq!(env.svm.get_balance(&shared_vault()), Some(rent));
+
+    withdraw_lamports(&mut env, &authority, rent).expect("withdraw_lamports failed");
+    assert!(!env.exists(&shared_vault()));
+}
+
+#[test]
+fn rejects_lamport_withdrawal_by_others() {
+    let mut env = TestEnv::new();
+    env.svm.airdrop(&shared_vault(), VAULT_LAMPORTS).unwrap();
+    let stranger = env.wallet();
+    let result = withdraw_lamports(&mut env, &stranger, VAULT_LAMPORTS);
+    assert_error(result, "Unauthorized");
+}
@@ -0,0 +1,186 @@
+//! `swap` in vesting mode locking the payout in an escrow, and `claim_vested` releasing it.
+
+mod common;
+
+use {
+    common::{
+        assert_error, ata, instruction, none, pda, swap_instruction, SwapArgs, SwapFixture,
+        TestEnv, VestingSchedule, ATA_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+    litesvm::types::TransactionResult,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+};
+
+const AMOUNT_IN: u64 = 1_000_000;
+const AMOUNT_PAID: u64 = 2_000_000;
+const VESTING_ID: u64 = 5;
+const CLIFF: i64 = 100;
+const DURATION: i64 = 1000;
+
+struct Fixture {
+    env: TestEnv,
+    swap: SwapFixture,
+    recipient: Keypair,
+    vesting: Pubkey,
+    escrow: Pubkey,
+}
+
+impl Fixture {
+    /// A swap paying a recipient that can sign for its claims, with a vesting account and
+    /// escrow in place of its token account.
+    fn new() -> Self {
+        let mut env = TestEnv::new();
+        let mut swap = env.swap_fixture(AMOUNT_IN, AMOUNT_PAID);
+        let recipient = env.wallet();
+        let id = VESTING_ID.to_le_bytes();
+        let vesting = pda(&[b"vesting", recipient.pubkey().as_ref(), &id]);
+        let escrow = pda(&[b"vesting_escrow", recipient.pubkey().as_ref(), &id]);
+        swap.accounts.recipient = recipient.pubkey();
+        swap.accounts.recipient_token_account = None;
+        swap.accounts.vesting = Some((vesting, escrow));
+
+        Self {
+            env,
+            swap,
+            recipient,
+            vesting,
+            escrow,
+        }
+    }
+
+    fn swap_instruction(&self, schedule: VestingSchedule) -> Instruction {
+        let mut args = SwapArgs::new(&self.swap.route, AMOUNT_PAID);
+        args.vesting_schedule = Some(schedule);
+        swap_instruction(&self.swap.accounts, args, &self.swap.route, &[])
+    }
+
+    /// Vests from now, unlocking nothing before the cliff.
+    fn schedule(&self) -> VestingSchedule {
+        let start = self.env.now();
+        VestingSchedule {
+            id: VESTING_ID,
+            start,
+            cliff: start + CLIFF,
+            end: start + DURATION,
+        }
+    }
+
+    fn claim_vested(&mut self) -> TransactionResult {
+        let recipient = self.recipient.pubkey();
+        let mint = self.swap.accounts.output_mint;
+        let ix = instruction(
+            "claim_vested",
+            (),
+            vec![
+                AccountMeta::new(recipient, true),
+                AccountMeta::new(self.vesting, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new(self.escrow, false),
+                AccountMeta::new(ata(&recipient, &mint), false),
+                AccountMeta::new_readonly(ATA_PROGRAM_ID, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        );
+        let recipient = self.recipient.insecure_clone();
+        self.env.send(&[ix], &[&recipient])
+    }
+
+    fn claimed(&self) -> u64 {
+        self.env.balance(&ata(
+            &self.recipient.pubkey(),
+            &self.swap.accounts.output_mint,
+        ))
+    }
+}
+
+#[test]
+fn locks_payout_and_releases_it_linearly() {
+    let mut fixture = Fixture::new();
+    let schedule = fixture.schedule();
+    let ix = fixture.swap_instruction(schedule);
+    fixture
+        .env
+        .send(&[ix], &[&fixture.swap.payer])
+        .expect("swap failed");
+    assert_eq!(fixture.env.balance(&fixture.escrow), AMOUNT_PAID);
+
+    let result = fixture.claim_vested();
+    assert_error(result, "NothingToClaim");
+
+    fixture.env.warp_seconds(DURATION / 2);
+    fixture.claim_vested().expect("claim_vested failed");
+    assert_eq!(fixture.claimed(), AMOUNT_PAID / 2);
+    let result = fixture.claim_vested();
+    assert_error(result, "NothingToClaim");
+
+    fixture.env.warp_seconds(DURATION);
+    fixture.claim_vested().expect("claim_vested failed");
+    assert_eq!(fixture.claimed(), AMOUNT_PAID);
+}
+
+#[test]
+fn closes_vesting_once_fully_claimed() {
+    let mut fixture = Fixture::new();
+    let schedule = fixture.schedule();
+    let ix = fixture.swap_instruction(schedule);
+    fixture
+        .env
+        .send(&[ix], &[&fixture.swap.payer])
+        .expect("swap failed");
+
+    fixture.env.warp_seconds(DURATION / 2);
+    fixture.claim_vested().expect("claim_vested failed");
+    assert!(fixture.env.exists(&fixture.vesting));
+    assert!(fixture.env.exists(&fixture.escrow));
+
+    //The last claim hands the rent of both accounts back, more than the claim's fee
+    let recipient = fixture.recipient.pubkey();
+    let before = fixture.env.svm.get_balance(&recipient).unwrap();
+    fixture.env.warp_seconds(DURATION);
+    fixture.claim_vested().expect("claim_vested failed");
+    assert_eq!(fixture.claimed(), AMOUNT_PAID);
+    assert!(!fixture.env.exists(&fixture.vesting));
+    assert!(!fixture.env.exists(&fixture.escrow));
+    assert!(fixture.env.svm.get_balance(&recipient).unwrap() > before);
+}
+
+#[test]
+fn rejects_schedule_ending_before_it_starts() {
+    let mut fixture = Fixture::new();
+    let schedule = fixture.schedule();
+    let ix = fixture.swap_instruction(VestingSchedule {
+        cliff: schedule.start,
+        end: schedule.start - 1,
+        ..schedule
+    });
+    let result = fixture.env.send(&[ix], &[&fixture.swap.payer]);
+    assert_error(result, "InvalidVestingSchedule");
+}
+
+#[test]
+fn rejects_cliff_after_end() {
+    let mut fixture = Fixture::new();
+    let schedule = fixture.schedule();
+    let ix = fixture.swap_instruction(VestingSchedule {
+        cliff: schedule.end + 1,
+        ..schedule
+    });
+    let result = fixture.env.send(&[ix], &[&fixture.swap.payer]);
+    assert_error(result, "InvalidVestingSchedule");
+}
+
+#[test]
+fn rejects_schedule_without_vesting_accounts() {
+    let mut fixture = Fixture::new();
+    let schedule = fixture.schedule();
+    let mut ix = fixture.swap_instruction(schedule);
+    //vesting and vesting_escrow
+    ix.accounts[18] = none();
+    ix.accounts[19] = none();
+    let result = fixture.env.send(&[ix], &[&fixture.swap.payer]);
+    assert_error(result, "VestingMismatch");
+}
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum SwapError {
+    #[msg("Signer is not the config authority")]
+    Unauthorized,
+    #[msg("Intent deadline has passed")]
+    IntentExpired,
+    #[msg("Intent nonce does not match the user's next nonce")]
+    InvalidNonce,
+    #[msg("Expected an ed25519 signature instruction right before this one")]
+    InvalidSignatureInstruction,
+    #[msg("Signature does not cover this intent or was not made by the user")]
+    SignatureMismatch,
+    #[msg("Accounts do not match the signed intent")]
+    IntentMismatch,
+    #[msg("Swap output is below the minimum amount out")]
+    SlippageExceeded,
+    #[msg("Delegation is not approved as delegate for this amount")]
+    DelegateNotApproved,
+    #[msg("Order has expired")]
+    OrderExpired,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Realised price is below the order's limit price")]
+    LimitPriceNotMet,
+    #[msg("Route must spend exactly the input moved into the vault")]
+    RouteInputMismatch,
+    #[msg("Interval must be greater than zero")]
+    InvalidInterval,
+    #[msg("DCA cycle is not eligible yet")]
+    CycleNotReady,
+    #[msg("Not enough unspent input left")]
+    InsufficientInput,
+    #[msg("Basis points must not exceed 10000")]
+    InvalidBps,
+    #[msg("Slice count must be between 1 and the total input")]
+    InvalidSliceCount,
+    #[msg("All slices of this order have been filled")]
+    OrderFilled,
+    #[msg("Minimum slot gap since the last fill has not elapsed")]
+    SlotGapNotElapsed,
+    #[msg("Average fill price deviates too far from the order's reference price")]
+    PriceDeviationExceeded,
+    #[msg("Withdrawal would leave the vault below rent exemption")]
+    VaultBelowRentExemption,
+    #[msg("Token account still holds a balance")]
+    NonZeroBalance,
+    #[msg("Too many entries for the list")]
+    ListTooLong,
+    #[msg("A callback needs its program account")]
+    MissingCallbackProgram,
+    #[msg("Callback program is not allowlisted")]
+    CallbackProgramNotAllowed,
+    #[msg("Callback wants more accounts than were passed")]
+    InvalidCallbackAccounts,
+    #[msg("Invoice has expired")]
+    InvoiceExpired,
+    #[msg("Invoice has already been paid")]
+    InvoiceAlreadyPaid,
+    #[msg("A receipt needs both its id and its account")]
+    ReceiptMismatch,
+    #[msg("Receipt retention period has not elapsed")]
+    RetentionNotElapsed,
+    #[msg("Vesting needs both its schedule and its accounts")]
+    VestingMismatch,
+    #[msg("Vesting schedule must satisfy start <= cliff <= end and start < end")]
+    InvalidVestingSchedule,
+    #[msg("Swap needs a recipient token account unless it vests")]
+    MissingRecipientTokenAccount,
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+    #[msg("Swap needs either both oracle price accounts or neither")]
+    OracleMismatch,
+    #[msg("Not a fully verified Pyth price update")]
+    InvalidOracleAccount,
+    #[msg("Oracle price update is for another feed")]
+    OracleFeedMismatch,
+    #[msg("Oracle price is too old")]
+    StaleOraclePrice,
+    #[msg("Swap rate deviates too far from the oracle rate")]
+    OracleDeviationExceeded,
+    #[msg("Volume cap for this window has been reached")]
+    RateLimitExceeded,
+    #[msg("Vault is not the integrator's vault")]
+    InvalidVault,
+    #[msg("Mint is not allowed by the integrator")]
+    MintNotAllowed,
+    #[msg("Signer is not one of the integrator's relayers")]
+    RelayerNotAllowed,
+    #[msg("Fee needs a token account of the integrator's fee treasury")]
+    InvalidFeeTreasury,
+    #[msg("Referral needs the referrer, its account and its escrow")]
+    MissingReferralAccounts,
+    #[msg("Payer can't refer its own swap")]
+    SelfReferral,
+    #[msg("Trigger order needs a stop-loss or a take-profit price")]
+    InvalidTrigger,
+    #[msg("Oracle price hasn't crossed the order's trigger prices")]
+    TriggerNotMet,
+    #[msg("Portfolio targets must be distinct mints adding up to 10000 bps")]
+    InvalidPortfolioTargets,
+    #[msg("Accounts don't match the portfolio's assets")]
+    InvalidPortfolioAccounts,
+    #[msg("Rebalance doesn't move the portfolio closer to its targets")]
+    RebalanceNotImproving,
+    #[msg("Rebalance reduced a portfolio balance other than the input's")]
+    PortfolioBalanceDecreased,
+    #[msg("Rebalance lost more of the portfolio's value than its max slippage")]
+    PortfolioValueLost,
+    #[msg("Config change is still timelocked")]
+    TimelockNotElapsed,
+    #[msg("Pending change is not the one this instruction applies")]
+    PendingChangeMismatch,
+    #[msg("Route program is not an allowlisted aggregator")]
+    AggregatorNotAllowed,
+    #[msg("Route instruction is not allowed for the aggregator")]
+    RouteInstructionNotAllowed,
+    #[msg("Memo is too long")]
+    MemoTooLong,
+    #[msg("Memo needs the memo program")]
+    MissingMemoProgram,
+    #[msg("Output mint can freeze or claw back the payout")]
+    DangerousMint,
+    #[msg("Route output doesn't cover the payout and the fee")]
+    OutputBelowPayout,
+    #[msg("Surplus destination must be the payer's or the surplus treasury's output account")]
+    InvalidSurplusDestination,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Integrator, Referrer};
+use crate::{INTEGRATOR_SEED, REFERRER_SEED};
+
+/// Lets `referrer` earn the referral share of the integrator's fee on its swaps paying out
+/// `mint`. Swaps can't name a referrer the integrator hasn't approved.
+pub fn handler(ctx: Context<ApproveReferrer>) -> Result<()> {
+    let referrer_account = &mut ctx.accounts.referrer_account;
+    referrer_account.integrator = ctx.accounts.integrator.key();
+    referrer_account.referrer = ctx.accounts.referrer.key();
+    referrer_account.mint = ctx.accounts.mint.key();
+    referrer_account.unclaimed = 0;
+    referrer_account.total_earned = 0;
+    referrer_account.bump = ctx.bumps.referrer_account;
+
+    msg!(
+        "Integrator {} approved referrer {} on {}",
+        ctx.accounts.integrator.id,
+        referrer_account.referrer,
+        referrer_account.mint
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApproveReferrer<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+      has_one=authority @ SwapError::Unauthorized,
+      seeds=[INTEGRATOR_SEED, &integrator.id.to_le_bytes()],
+      bump=integrator.bump
+    )]
+    pub integrator: Account<'info, Integrator>,
+
+    pub referrer: SystemAccount<'info>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      init,
+      payer=authority,
+      space=8 + Referrer::INIT_SPACE,
+      seeds=[
+        REFERRER_SEED,
+        integrator.key().as_ref(),
+        referrer.key().as_ref(),
+        mint.key().as_ref()
+      ],
+      bump
+    )]
+    pub referrer_account: Box<Account<'info, Referrer>>,
+
+    //Anyone can create an ATA, so an existing escrow must not block the approval
+    #[account(
+      init_if_needed,
+      payer=authority,
+      associated_token::mint=mint,
+      associated_token::authority=referrer_account,
+      associated_token::token_program=mint_token_program,
+    )]
+    pub referrer_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
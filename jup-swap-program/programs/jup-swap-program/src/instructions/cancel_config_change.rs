@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::state::{Config, PendingChange};
+use crate::{CONFIG_SEED, PENDING_CHANGE_SEED};
+
+pub fn handler(ctx: Context<CancelConfigChange>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    require!(
+        signer == ctx.accounts.config.guardian || signer == ctx.accounts.pending_change.proposer,
+        SwapError::Unauthorized
+    );
+
+    msg!(
+        "Config change {} cancelled by {}",
+        ctx.accounts.pending_change.id,
+        signer
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    /// The guardian, or the proposer withdrawing its own change
+    pub signer: Signer<'info>,
+
+    /// CHECK: receives the pending change's rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+      mut,
+      close=proposer,
+      has_one=proposer,
+      seeds=[PENDING_CHANGE_SEED, proposer.key().as_ref(), &pending_change.id.to_le_bytes()],
+      bump=pending_change.bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+}
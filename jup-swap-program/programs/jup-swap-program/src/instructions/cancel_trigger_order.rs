@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::TriggerOrder;
+use crate::utils::{close_token_account_signed, transfer_signed};
+use crate::TRIGGER_ORDER_SEED;
+
+pub fn handler(ctx: Context<CancelTriggerOrder>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    let owner_key = order.owner;
+    let id_bytes = order.id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        TRIGGER_ORDER_SEED,
+        owner_key.as_ref(),
+        &id_bytes,
+        &[order.bump],
+    ]];
+
+    //Return the escrowed input and the escrow's rent to the owner
+    transfer_signed(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner_input_token_account.to_account_info(),
+        order.to_account_info(),
+        ctx.accounts.escrow_token_account.amount,
+        signer_seeds,
+    )?;
+    close_token_account_signed(
+        &ctx.accounts.input_mint_token_program,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        order.to_account_info(),
+        signer_seeds,
+    )?;
+
+    msg!("Trigger order {} cancelled", order.id);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelTriggerOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+      mut,
+      close=owner,
+      has_one=owner,
+      has_one=input_mint,
+      seeds=[TRIGGER_ORDER_SEED, owner.key().as_ref(), &order.id.to_le_bytes()],
+      bump=order.bump
+    )]
+    pub order: Account<'info, TriggerOrder>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=order,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::TwapOrder;
+use crate::utils::{close_token_account_signed, transfer_signed};
+use crate::TWAP_SEED;
+
+pub fn handler(ctx: Context<CancelTwap>) -> Result<()> {
+    let twap = &ctx.accounts.twap;
+    let owner_key = twap.owner;
+    let id_bytes = twap.id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[TWAP_SEED, owner_key.as_ref(), &id_bytes, &[twap.bump]]];
+
+    //Return whatever input hasn't been filled yet along with the escrow's rent
+    let unfilled = ctx.accounts.escrow_token_account.amount;
+    if unfilled > 0 {
+        transfer_signed(
+            &ctx.accounts.input_mint_token_program,
+            &ctx.accounts.input_mint,
+            ctx.accounts.escrow_token_account.to_account_info(),
+            ctx.accounts.owner_input_token_account.to_account_info(),
+            twap.to_account_info(),
+            unfilled,
+            signer_seeds,
+        )?;
+    }
+    close_token_account_signed(
+        &ctx.accounts.input_mint_token_program,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        twap.to_account_info(),
+        signer_seeds,
+    )?;
+
+    msg!(
+        "TWAP {} cancelled, returned {} unfilled input",
+        twap.id,
+        unfilled
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelTwap<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+      mut,
+      close=owner,
+      has_one=owner,
+      has_one=input_mint,
+      seeds=[TWAP_SEED, owner.key().as_ref(), &twap.id.to_le_bytes()],
+      bump=twap.bump
+    )]
+    pub twap: Account<'info, TwapOrder>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=twap,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
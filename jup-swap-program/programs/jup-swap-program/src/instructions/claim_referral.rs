@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::Referrer;
+use crate::utils::transfer_signed;
+use crate::REFERRER_SEED;
+
+pub fn handler(ctx: Context<ClaimReferral>) -> Result<()> {
+    let referrer = &ctx.accounts.referrer_account;
+    let claimable = referrer.unclaimed;
+    require_gt!(claimable, 0, SwapError::NothingToClaim);
+
+    let integrator_key = referrer.integrator;
+    let referrer_key = referrer.referrer;
+    let mint_key = referrer.mint;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        REFERRER_SEED,
+        integrator_key.as_ref(),
+        referrer_key.as_ref(),
+        mint_key.as_ref(),
+        &[referrer.bump],
+    ]];
+
+    transfer_signed(
+        &ctx.accounts.mint_token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.referrer_token_account.to_account_info(),
+        referrer.to_account_info(),
+        claimable,
+        signer_seeds,
+    )?;
+
+    let referrer = &mut ctx.accounts.referrer_account;
+    referrer.unclaimed = 0;
+
+    msg!(
+        "Referrer {} claimed {} of {}, {} earned so far",
+        referrer.referrer,
+        claimable,
+        referrer.mint,
+        referrer.total_earned
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+      mut,
+      has_one=referrer,
+      has_one=mint,
+      seeds=[
+        REFERRER_SEED,
+        referrer_account.integrator.as_ref(),
+        referrer.key().as_ref(),
+        mint.key().as_ref()
+      ],
+      bump=referrer_account.bump
+    )]
+    pub referrer_account: Box<Account<'info, Referrer>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=mint,
+      associated_token::authority=referrer_account,
+      associated_token::token_program=mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      init_if_needed,
+      payer=referrer,
+      associated_token::mint=mint,
+      associated_token::authority=referrer,
+      associated_token::token_program=mint_token_program,
+    )]
+    pub referrer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
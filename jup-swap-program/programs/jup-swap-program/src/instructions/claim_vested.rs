@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::Vesting;
+use crate::utils::{close_token_account_signed, transfer_signed};
+use crate::{VESTING_ESCROW_SEED, VESTING_SEED};
+
+pub fn handler(ctx: Context<ClaimVested>) -> Result<()> {
+    let vesting = &ctx.accounts.vesting;
+    let vested = vesting.vested_amount(Clock::get()?.unix_timestamp)?;
+    let claimable = vested
+        .checked_sub(vesting.claimed)
+        .ok_or(SwapError::MathOverflow)?;
+    require_gt!(claimable, 0, SwapError::NothingToClaim);
+
+    let recipient_key = vesting.recipient;
+    let id_bytes = vesting.id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        VESTING_SEED,
+        recipient_key.as_ref(),
+        &id_bytes,
+        &[vesting.bump],
+    ]];
+
+    transfer_signed(
+        &ctx.accounts.mint_token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.recipient_token_account.to_account_info(),
+        vesting.to_account_info(),
+        claimable,
+        signer_seeds,
+    )?;
+
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.claimed = vested;
+
+    msg!(
+        "Vesting {} claimed {}, {} of {} claimed so far",
+        vesting.id,
+        claimable,
+        vesting.claimed,
+        vesting.total
+    );
+
+    //Nothing is left to claim, hand the rent of both accounts back to the recipient
+    if vesting.claimed == vesting.total {
+        close_token_account_signed(
+            &ctx.accounts.mint_token_program,
+            ctx.accounts.escrow_token_account.to_account_info(),
+            ctx.accounts.recipient.to_account_info(),
+            ctx.accounts.vesting.to_account_info(),
+            signer_seeds,
+        )?;
+        ctx.accounts
+            .vesting
+            .close(ctx.accounts.recipient.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    #[account(
+      mut,
+      has_one=recipient,
+      has_one=mint,
+      seeds=[VESTING_SEED, recipient.key().as_ref(), &vesting.id.to_le_bytes()],
+      bump=vesting.bump
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      seeds=[VESTING_ESCROW_SEED, recipient.key().as_ref(), &vesting.id.to_le_bytes()],
+      bump,
+      token::mint=mint,
+      token::authority=vesting,
+      token::token_program=mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      init_if_needed,
+      payer=recipient,
+      associated_token::mint=mint,
+      associated_token::authority=recipient,
+      associated_token::token_program=mint_token_program,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{revoke, Revoke, TokenAccount, TokenInterface};
+
+use crate::state::Delegation;
+use crate::DELEGATION_SEED;
+
+pub fn handler(ctx: Context<CloseDelegation>) -> Result<()> {
+    //Only revoke our own approval, the owner may have approved someone else since
+    let owner_input_token_account = &ctx.accounts.owner_input_token_account;
+    if owner_input_token_account.delegate == Some(ctx.accounts.delegation.key()).into() {
+        let cpi_accounts = Revoke {
+            source: owner_input_token_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.input_mint_token_program.to_account_info();
+        revoke(CpiContext::new(cpi_program, cpi_accounts))?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseDelegation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+      mut,
+      close=owner,
+      has_one=owner,
+      seeds=[DELEGATION_SEED, owner_input_token_account.key().as_ref()],
+      bump=delegation.bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(
+      mut,
+      token::mint=delegation.input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::state::{Receipt, RECEIPT_RETENTION_PERIOD};
+use crate::RECEIPT_SEED;
+
+/// Closes a receipt kept for `RECEIPT_RETENTION_PERIOD`, refunding its rent. Its id can be used
+/// again afterwards: a swap retried with it pays out a second time.
+pub fn handler(ctx: Context<CloseReceipt>) -> Result<()> {
+    let receipt = &ctx.accounts.receipt;
+    let closable_at = receipt
+        .created_at
+        .checked_add(RECEIPT_RETENTION_PERIOD)
+        .ok_or(SwapError::MathOverflow)?;
+    require_gte!(
+        Clock::get()?.unix_timestamp,
+        closable_at,
+        SwapError::RetentionNotElapsed
+    );
+
+    msg!("Receipt {} closed", receipt.id);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseReceipt<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+      mut,
+      close=payer,
+      has_one=payer,
+      seeds=[RECEIPT_SEED, payer.key().as_ref(), &receipt.id.to_le_bytes()],
+      bump=receipt.bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+}
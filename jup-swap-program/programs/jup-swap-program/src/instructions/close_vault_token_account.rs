@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Config, Integrator};
+use crate::utils::close_token_account_signed;
+use crate::{CONFIG_SEED, INTEGRATOR_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<CloseVaultTokenAccount>) -> Result<()> {
+    //Integrator vaults are closed by the integrator's authority, the shared one by the config's
+    let authority = match &ctx.accounts.integrator {
+        Some(integrator) => integrator.authority,
+        None => ctx.accounts.config.authority,
+    };
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        authority,
+        SwapError::Unauthorized
+    );
+
+    //Derive the vault the same way `swap` does
+    let integrator_key = ctx
+        .accounts
+        .integrator
+        .as_ref()
+        .map(|integrator| integrator.key());
+    let mut vault_seeds: Vec<&[u8]> = vec![VAULT_SEED];
+    if let Some(integrator_key) = &integrator_key {
+        vault_seeds.push(integrator_key.as_ref());
+    }
+    let (vault_key, vault_bump) = Pubkey::find_program_address(&vault_seeds, ctx.program_id);
+    require_keys_eq!(ctx.accounts.vault.key(), vault_key, SwapError::InvalidVault);
+    let vault_bump = [vault_bump];
+    vault_seeds.push(&vault_bump);
+
+    close_token_account_signed(
+        &ctx.accounts.token_program,
+        ctx.accounts.vault_token_account.to_account_info(),
+        ctx.accounts.rent_receiver.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        &[&vault_seeds],
+    )?;
+
+    msg!(
+        "Closed vault token account {} for mint {}",
+        ctx.accounts.vault_token_account.key(),
+        ctx.accounts.mint.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseVaultTokenAccount<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The shared vault, or the integrator's when one is passed, checked in the handler
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=mint,
+      associated_token::authority=vault,
+      associated_token::token_program=token_program,
+      constraint=vault_token_account.amount == 0 @ SwapError::NonZeroBalance,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: any account can receive the reclaimed rent
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    #[account(
+      seeds=[INTEGRATOR_SEED, &integrator.id.to_le_bytes()],
+      bump=integrator.bump
+    )]
+    pub integrator: Option<Account<'info, Integrator>>,
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::DcaOrder;
+use crate::utils::transfer_tokens;
+use crate::DCA_SEED;
+
+pub fn handler(
+    ctx: Context<CreateDca>,
+    id: u64,
+    total_input: u64,
+    amount_per_cycle: u64,
+    interval: i64,
+    min_out_per_cycle: u64,
+) -> Result<()> {
+    require!(
+        total_input > 0 && amount_per_cycle > 0,
+        SwapError::ZeroAmount
+    );
+    require_gte!(total_input, amount_per_cycle, SwapError::InsufficientInput);
+    require_gt!(interval, 0, SwapError::InvalidInterval);
+
+    let dca = &mut ctx.accounts.dca;
+    dca.owner = ctx.accounts.owner.key();
+    dca.id = id;
+    dca.recipient = ctx.accounts.recipient.key();
+    dca.input_mint = ctx.accounts.input_mint.key();
+    dca.output_mint = ctx.accounts.output_mint.key();
+    dca.total_input = total_input;
+    dca.amount_per_cycle = amount_per_cycle;
+    dca.interval = interval;
+    dca.min_out_per_cycle = min_out_per_cycle;
+    //The first cycle can run straight away
+    dca.next_cycle_at = Clock::get()?.unix_timestamp;
+    dca.input_spent = 0;
+    dca.output_received = 0;
+    dca.bump = ctx.bumps.dca;
+
+    transfer_tokens(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.owner_input_token_account.to_account_info(),
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        total_input,
+    )?;
+
+    msg!(
+        "DCA {} created: {} every {}s out of {}",
+        id,
+        amount_per_cycle,
+        interval,
+        total_input
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateDca<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+      init,
+      payer=owner,
+      space=8 + DcaOrder::INIT_SPACE,
+      seeds=[DCA_SEED, owner.key().as_ref(), &id.to_le_bytes()],
+      bump
+    )]
+    pub dca: Account<'info, DcaOrder>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      init,
+      payer=owner,
+      associated_token::mint=input_mint,
+      associated_token::authority=dca,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{approve, Approve, Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::Delegation;
+use crate::DELEGATION_SEED;
+
+pub fn handler(
+    ctx: Context<CreateDelegation>,
+    keeper: Pubkey,
+    cap: u64,
+    rate_in: u64,
+    min_out_per_rate_in: u64,
+) -> Result<()> {
+    require!(
+        rate_in > 0 && min_out_per_rate_in > 0,
+        SwapError::ZeroAmount
+    );
+
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.owner = ctx.accounts.owner.key();
+    delegation.keeper = keeper;
+    delegation.input_mint = ctx.accounts.input_mint.key();
+    delegation.input_token_account = ctx.accounts.owner_input_token_account.key();
+    delegation.output_mint = ctx.accounts.output_mint.key();
+    delegation.rate_in = rate_in;
+    delegation.min_out_per_rate_in = min_out_per_rate_in;
+    delegation.bump = ctx.bumps.delegation;
+
+    //Approve the delegation itself rather than the vault, which signs every route. Custody stays
+    //with the owner and `cap` bounds what keepers can pull
+    let cpi_accounts = Approve {
+        to: ctx.accounts.owner_input_token_account.to_account_info(),
+        delegate: ctx.accounts.delegation.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.input_mint_token_program.to_account_info();
+    approve(CpiContext::new(cpi_program, cpi_accounts), cap)?;
+
+    msg!(
+        "Delegated {} to keeper {}, at least {} out per {} in",
+        cap,
+        keeper,
+        min_out_per_rate_in,
+        rate_in
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateDelegation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+      init,
+      payer=owner,
+      space=8 + Delegation::INIT_SPACE,
+      seeds=[DELEGATION_SEED, owner_input_token_account.key().as_ref()],
+      bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
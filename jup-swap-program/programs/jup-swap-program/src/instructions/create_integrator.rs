@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::instructions::execute_config_change::require_executable;
+use crate::state::{Config, ConfigChange, Integrator, PendingChange};
+use crate::{CONFIG_SEED, INTEGRATOR_SEED, PENDING_CHANGE_SEED};
+
+/// Applies a queued [`ConfigChange::NewIntegrator`].
+pub fn handler(
+    ctx: Context<CreateIntegrator>,
+    id: u64,
+    authority: Pubkey,
+    fee_bps: u16,
+    fee_treasury: Pubkey,
+) -> Result<()> {
+    let pending = &ctx.accounts.pending_change;
+    require_executable(pending, &ctx.accounts.config, None)?;
+    require!(
+        pending.change
+            == ConfigChange::NewIntegrator {
+                id,
+                authority,
+                fee_bps,
+                fee_treasury,
+            },
+        SwapError::PendingChangeMismatch
+    );
+
+    let integrator = &mut ctx.accounts.integrator;
+    integrator.id = id;
+    integrator.authority = authority;
+    integrator.fee_bps = fee_bps;
+    integrator.fee_treasury = fee_treasury;
+    integrator.bump = ctx.bumps.integrator;
+
+    msg!(
+        "Integrator {} created, authority: {}, fee: {} bps",
+        id,
+        authority,
+        fee_bps
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateIntegrator<'info> {
+    /// Anyone, pays for the integrator account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: receives the pending change's rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    //Only the config authority onboards integrators, through the timelock
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+      init,
+      payer=payer,
+      space=8 + Integrator::INIT_SPACE,
+      seeds=[INTEGRATOR_SEED, &id.to_le_bytes()],
+      bump
+    )]
+    pub integrator: Account<'info, Integrator>,
+
+    #[account(
+      mut,
+      close=proposer,
+      has_one=proposer,
+      seeds=[PENDING_CHANGE_SEED, proposer.key().as_ref(), &pending_change.id.to_le_bytes()],
+      bump=pending_change.bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    pub system_program: Program<'info, System>,
+}
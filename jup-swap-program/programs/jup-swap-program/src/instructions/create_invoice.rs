@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::SwapError;
+use crate::state::Invoice;
+use crate::INVOICE_SEED;
+
+pub fn handler(
+    ctx: Context<CreateInvoice>,
+    id: u64,
+    amount: u64,
+    expiry: i64,
+    memo_hash: [u8; 32],
+) -> Result<()> {
+    require_gt!(amount, 0, SwapError::ZeroAmount);
+    require_gt!(
+        expiry,
+        Clock::get()?.unix_timestamp,
+        SwapError::InvalidExpiry
+    );
+
+    let invoice = &mut ctx.accounts.invoice;
+    invoice.merchant = ctx.accounts.merchant.key();
+    invoice.id = id;
+    invoice.output_mint = ctx.accounts.output_mint.key();
+    invoice.amount = amount;
+    invoice.expiry = expiry;
+    invoice.memo_hash = memo_hash;
+    invoice.paid = false;
+    invoice.bump = ctx.bumps.invoice;
+
+    msg!(
+        "Invoice {} created: {} of {} due by {}",
+        id,
+        amount,
+        invoice.output_mint,
+        expiry
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateInvoice<'info> {
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    #[account(
+      init,
+      payer=merchant,
+      space=8 + Invoice::INIT_SPACE,
+      seeds=[INVOICE_SEED, merchant.key().as_ref(), &id.to_le_bytes()],
+      bump
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+}
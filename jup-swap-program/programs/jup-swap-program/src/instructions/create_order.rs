@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::Order;
+use crate::utils::transfer_tokens;
+use crate::ORDER_SEED;
+
+pub fn handler(
+    ctx: Context<CreateOrder>,
+    id: u64,
+    amount_in: u64,
+    min_amount_out: u64,
+    expiry: i64,
+) -> Result<()> {
+    require!(amount_in > 0 && min_amount_out > 0, SwapError::ZeroAmount);
+    require_gt!(
+        expiry,
+        Clock::get()?.unix_timestamp,
+        SwapError::InvalidExpiry
+    );
+
+    let order = &mut ctx.accounts.order;
+    order.owner = ctx.accounts.owner.key();
+    order.id = id;
+    order.input_mint = ctx.accounts.input_mint.key();
+    order.output_mint = ctx.accounts.output_mint.key();
+    order.amount_in = amount_in;
+    order.min_amount_out = min_amount_out;
+    order.expiry = expiry;
+    order.bump = ctx.bumps.order;
+
+    //Escrow the input in the order's own token account until it's filled or cancelled
+    transfer_tokens(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.owner_input_token_account.to_account_info(),
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        amount_in,
+    )?;
+
+    msg!(
+        "Order {} created: {} in for at least {} out",
+        id,
+        amount_in,
+        min_amount_out
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+      init,
+      payer=owner,
+      space=8 + Order::INIT_SPACE,
+      seeds=[ORDER_SEED, owner.key().as_ref(), &id.to_le_bytes()],
+      bump
+    )]
+    pub order: Account<'info, Order>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      init,
+      payer=owner,
+      associated_token::mint=input_mint,
+      associated_token::authority=order,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Portfolio, PortfolioAsset};
+use crate::PORTFOLIO_SEED;
+
+pub fn handler(
+    ctx: Context<CreatePortfolio>,
+    id: u64,
+    assets: Vec<PortfolioAsset>,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    Portfolio::validate_targets(&assets, max_slippage_bps)?;
+
+    let portfolio = &mut ctx.accounts.portfolio;
+    portfolio.authority = ctx.accounts.authority.key();
+    portfolio.id = id;
+    portfolio.assets = assets;
+    portfolio.max_slippage_bps = max_slippage_bps;
+    portfolio.bump = ctx.bumps.portfolio;
+
+    msg!(
+        "Portfolio {} created: {} assets, {} bps max slippage",
+        id,
+        portfolio.assets.len(),
+        max_slippage_bps
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreatePortfolio<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+      init,
+      payer=authority,
+      space=8 + Portfolio::INIT_SPACE,
+      seeds=[PORTFOLIO_SEED, authority.key().as_ref(), &id.to_le_bytes()],
+      bump
+    )]
+    pub portfolio: Account<'info, Portfolio>,
+
+    pub system_program: Program<'info, System>,
+}
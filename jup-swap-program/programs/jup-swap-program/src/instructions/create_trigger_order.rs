@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Trigger, TriggerOrder};
+use crate::utils::transfer_tokens;
+use crate::TRIGGER_ORDER_SEED;
+
+pub fn handler(
+    ctx: Context<CreateTriggerOrder>,
+    id: u64,
+    amount_in: u64,
+    min_amount_out: u64,
+    trigger: Trigger,
+) -> Result<()> {
+    require!(amount_in > 0 && min_amount_out > 0, SwapError::ZeroAmount);
+    require!(
+        trigger.stop_loss_price.is_some() || trigger.take_profit_price.is_some(),
+        SwapError::InvalidTrigger
+    );
+    //A stop-loss at or above the take-profit would fire at any price
+    if let (Some(stop_loss_price), Some(take_profit_price)) =
+        (trigger.stop_loss_price, trigger.take_profit_price)
+    {
+        require_gt!(
+            take_profit_price,
+            stop_loss_price,
+            SwapError::InvalidTrigger
+        );
+    }
+
+    let order = &mut ctx.accounts.order;
+    order.owner = ctx.accounts.owner.key();
+    order.id = id;
+    order.input_mint = ctx.accounts.input_mint.key();
+    order.output_mint = ctx.accounts.output_mint.key();
+    order.amount_in = amount_in;
+    order.min_amount_out = min_amount_out;
+    order.trigger = trigger;
+    order.bump = ctx.bumps.order;
+
+    //Escrow the position in the order's own token account until it's triggered or cancelled
+    transfer_tokens(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.owner_input_token_account.to_account_info(),
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        amount_in,
+    )?;
+
+    msg!(
+        "Trigger order {} created: {} in for at least {} out, stop-loss: {:?}, take-profit: {:?}",
+        id,
+        amount_in,
+        min_amount_out,
+        trigger.stop_loss_price,
+        trigger.take_profit_price
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateTriggerOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+      init,
+      payer=owner,
+      space=8 + TriggerOrder::INIT_SPACE,
+      seeds=[TRIGGER_ORDER_SEED, owner.key().as_ref(), &id.to_le_bytes()],
+      bump
+    )]
+    pub order: Account<'info, TriggerOrder>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      init,
+      payer=owner,
+      associated_token::mint=input_mint,
+      associated_token::authority=order,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
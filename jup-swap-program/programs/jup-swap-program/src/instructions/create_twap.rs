@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{TwapLimits, TwapOrder};
+use crate::utils::transfer_tokens;
+use crate::{BPS_DENOMINATOR, TWAP_SEED};
+
+pub fn handler(
+    ctx: Context<CreateTwap>,
+    id: u64,
+    total_input: u64,
+    num_slices: u32,
+    min_slot_gap: u64,
+    limits: TwapLimits,
+) -> Result<()> {
+    require!(
+        num_slices > 0 && u64::from(num_slices) <= total_input,
+        SwapError::InvalidSliceCount
+    );
+    require_gte!(
+        BPS_DENOMINATOR,
+        u64::from(limits.max_deviation_bps),
+        SwapError::InvalidBps
+    );
+
+    let twap = &mut ctx.accounts.twap;
+    twap.owner = ctx.accounts.owner.key();
+    twap.id = id;
+    twap.recipient = ctx.accounts.recipient.key();
+    twap.input_mint = ctx.accounts.input_mint.key();
+    twap.output_mint = ctx.accounts.output_mint.key();
+    twap.total_input = total_input;
+    twap.num_slices = num_slices;
+    twap.slices_filled = 0;
+    twap.min_slot_gap = min_slot_gap;
+    twap.last_fill_slot = 0;
+    twap.filled_input = 0;
+    twap.filled_output = 0;
+    twap.limits = limits;
+    twap.bump = ctx.bumps.twap;
+
+    transfer_tokens(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.owner_input_token_account.to_account_info(),
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        total_input,
+    )?;
+
+    msg!(
+        "TWAP {} created: {} in {} slices, {} slots apart",
+        id,
+        total_input,
+        num_slices,
+        min_slot_gap
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateTwap<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+      init,
+      payer=owner,
+      space=8 + TwapOrder::INIT_SPACE,
+      seeds=[TWAP_SEED, owner.key().as_ref(), &id.to_le_bytes()],
+      bump
+    )]
+    pub twap: Account<'info, TwapOrder>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      init,
+      payer=owner,
+      associated_token::mint=input_mint,
+      associated_token::authority=twap,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
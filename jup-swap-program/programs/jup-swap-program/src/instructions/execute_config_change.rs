@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::instructions::queue_config_change::authorize;
+use crate::state::{Config, ConfigChange, Integrator, PendingChange};
+use crate::{CONFIG_SEED, PENDING_CHANGE_SEED};
+
+pub fn handler(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let pending = &ctx.accounts.pending_change;
+    require_executable(
+        pending,
+        &ctx.accounts.config,
+        ctx.accounts.integrator.as_deref(),
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    match pending.change.clone() {
+        ConfigChange::Authority { authority } => config.authority = authority,
+        ConfigChange::Guardian { guardian } => config.guardian = guardian,
+        ConfigChange::TimelockDelay { delay } => config.timelock_delay = delay,
+        ConfigChange::Aggregators { aggregators } => config.aggregators = aggregators,
+        ConfigChange::CallbackPrograms { programs } => config.callback_programs = programs,
+        ConfigChange::OracleConfig {
+            max_deviation_bps,
+            max_age,
+            feeds,
+        } => {
+            config.oracle_max_deviation_bps = max_deviation_bps;
+            config.oracle_max_age = max_age;
+            config.oracle_feeds = feeds;
+        }
+        ConfigChange::MintRisks {
+            blocked_mint_risks,
+            trusted_mints,
+        } => {
+            config.blocked_mint_risks = blocked_mint_risks;
+            config.trusted_mints = trusted_mints;
+        }
+        ConfigChange::SurplusTreasury { surplus_treasury } => {
+            config.surplus_treasury = surplus_treasury
+        }
+        ConfigChange::RateLimits {
+            window_slots,
+            default_mint_cap,
+            default_caller_cap,
+        } => {
+            config.rate_limit_window_slots = window_slots;
+            config.default_mint_cap = default_mint_cap;
+            config.default_caller_cap = default_caller_cap;
+        }
+        ConfigChange::Integrator {
+            authority,
+            fee_bps,
+            fee_treasury,
+            referral_share_bps,
+            allowed_mints,
+            relayers,
+            ..
+        } => {
+            let integrator = ctx
+                .accounts
+                .integrator
+                .as_mut()
+                .ok_or(SwapError::Unauthorized)?;
+            integrator.authority = authority;
+            integrator.fee_bps = fee_bps;
+            integrator.fee_treasury = fee_treasury;
+            integrator.referral_share_bps = referral_share_bps;
+            integrator.allowed_mints = allowed_mints;
+            integrator.relayers = relayers;
+        }
+        //These create accounts, so they are applied by their own instructions
+        ConfigChange::MintRateLimit { .. }
+        | ConfigChange::CallerRateLimit { .. }
+        | ConfigChange::NewIntegrator { .. } => return err!(SwapError::PendingChangeMismatch),
+    }
+
+    msg!(
+        "Config change {} executed: {:?}",
+        ctx.accounts.pending_change.id,
+        ctx.accounts.pending_change.change
+    );
+
+    Ok(())
+}
+
+/// Fails unless `pending`'s timelock has passed and its proposer still holds the authority
+/// over the change.
+pub fn require_executable(
+    pending: &PendingChange,
+    config: &Config,
+    integrator: Option<&Account<Integrator>>,
+) -> Result<()> {
+    require_gte!(
+        Clock::get()?.unix_timestamp,
+        pending.eta,
+        SwapError::TimelockNotElapsed
+    );
+    //The proposer may have lost the authority while the change was queued
+    authorize(config, integrator, &pending.change, &pending.proposer)
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    /// CHECK: receives the pending change's rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+      mut,
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// Only for integrator changes
+    #[account(mut)]
+    pub integrator: Option<Box<Account<'info, Integrator>>>,
+
+    #[account(
+      mut,
+      close=proposer,
+      has_one=proposer,
+      seeds=[PENDING_CHANGE_SEED, proposer.key().as_ref(), &pending_change.id.to_le_bytes()],
+      bump=pending_change.bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+}
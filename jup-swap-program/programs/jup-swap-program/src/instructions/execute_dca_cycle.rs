@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Config, DcaOrder, RateLimit};
+use crate::utils::{swap_exact_in, transfer_signed};
+use crate::{CONFIG_SEED, DCA_SEED, MINT_RATE_LIMIT_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<ExecuteDcaCycle>, data: Vec<u8>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let dca = &ctx.accounts.dca;
+    require_gte!(now, dca.next_cycle_at, SwapError::CycleNotReady);
+
+    //The last cycle swaps whatever is left and its minimum out is scaled down to match
+    let amount_in = dca
+        .amount_per_cycle
+        .min(ctx.accounts.escrow_token_account.amount);
+    require!(amount_in > 0, SwapError::InsufficientInput);
+    let min_out = u64::try_from(
+        u128::from(dca.min_out_per_cycle) * u128::from(amount_in)
+            / u128::from(dca.amount_per_cycle),
+    )
+    .map_err(|_| SwapError::MathOverflow)?;
+
+    let owner_key = dca.owner;
+    let id_bytes = dca.id.to_le_bytes();
+    let dca_seeds: &[&[&[u8]]] = &[&[DCA_SEED, owner_key.as_ref(), &id_bytes, &[dca.bump]]];
+    let vault_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+    transfer_signed(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.vault_input_token_account.to_account_info(),
+        dca.to_account_info(),
+        amount_in,
+        dca_seeds,
+    )?;
+
+    let amount_out = swap_exact_in(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        ctx.remaining_accounts,
+        &ctx.accounts.vault.key(),
+        &mut ctx.accounts.vault_input_token_account,
+        &mut ctx.accounts.vault_output_token_account,
+        amount_in,
+        data,
+        vault_seeds,
+    )?;
+
+    //Count the output paid out of the vault towards the output mint's volume for this window
+    let config = &ctx.accounts.config;
+    let mint_rate_limit = &mut ctx.accounts.mint_rate_limit;
+    mint_rate_limit.bump = ctx.bumps.mint_rate_limit;
+    mint_rate_limit.record(
+        Clock::get()?.slot,
+        config.rate_limit_window_slots,
+        config.default_mint_cap,
+        amount_out,
+    )?;
+    require_gte!(amount_out, min_out, SwapError::SlippageExceeded);
+
+    transfer_signed(
+        &ctx.accounts.output_mint_token_program,
+        &ctx.accounts.output_mint,
+        ctx.accounts.vault_output_token_account.to_account_info(),
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        amount_out,
+        vault_seeds,
+    )?;
+
+    //Schedule from now rather than the previous slot so a late keeper can't burst cycles
+    let dca = &mut ctx.accounts.dca;
+    dca.next_cycle_at = now
+        .checked_add(dca.interval)
+        .ok_or(SwapError::MathOverflow)?;
+    dca.input_spent = dca
+        .input_spent
+        .checked_add(amount_in)
+        .ok_or(SwapError::MathOverflow)?;
+    dca.output_received = dca
+        .output_received
+        .checked_add(amount_out)
+        .ok_or(SwapError::MathOverflow)?;
+
+    msg!("DCA {} cycle: {} in, {} out", dca.id, amount_in, amount_out);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDcaCycle<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+      mut,
+      has_one=recipient,
+      has_one=input_mint,
+      has_one=output_mint,
+      seeds=[DCA_SEED, dca.owner.as_ref(), &dca.id.to_le_bytes()],
+      bump=dca.bump
+    )]
+    pub dca: Box<Account<'info, DcaOrder>>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=dca,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub vault_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub vault_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint=output_mint,
+        associated_token::authority=recipient,
+        associated_token::token_program=output_mint_token_program,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub recipient: SystemAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer=keeper,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[MINT_RATE_LIMIT_SEED, vault.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_rate_limit: Box<Account<'info, RateLimit>>,
+    pub system_program: Program<'info, System>,
+}
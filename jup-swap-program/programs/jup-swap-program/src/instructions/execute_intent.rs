@@ -0,0 +1,327 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::instructions::{self, load_current_index_checked, load_instruction_at_checked},
+};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Config, RateLimit, UserNonce};
+use crate::utils::{swap_exact_in, transfer_signed};
+use crate::{CONFIG_SEED, INTENT_AUTHORITY_SEED, MINT_RATE_LIMIT_SEED, NONCE_SEED, VAULT_SEED};
+
+// Layout of the ed25519 precompile instruction data for a single signature:
+// [num_signatures: u8, padding: u8, Ed25519SignatureOffsets (7 x u16)]
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_HEADER_LEN: usize = ED25519_OFFSETS_START + 14;
+const ED25519_PUBKEY_LEN: usize = 32;
+// Instruction index the precompile uses for "data lives in this same instruction"
+const ED25519_CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// A swap authorised off-chain by `user`. The user signs [`SwapIntent::message`] with their
+/// wallet and any relayer can submit it, paying the fees. The input is pulled through the
+/// user's `[INTENT_AUTHORITY_SEED, user]` PDA, which they approve as delegate beforehand.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapIntent {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// Input amount pulled from the user's token account
+    pub amount: u64,
+    /// Minimum output the recipient must receive, after the relayer fee
+    pub min_out: u64,
+    pub recipient: Pubkey,
+    /// Unix timestamp after which the intent can no longer be executed
+    pub deadline: i64,
+    pub nonce: u64,
+    /// Output tokens the user allows the relayer to keep as reimbursement
+    pub relayer_fee: u64,
+}
+
+impl SwapIntent {
+    /// Bytes the user signs: the program id followed by the borsh encoded intent, so the
+    /// signature can't be replayed against another deployment.
+    pub fn message(&self) -> Result<Vec<u8>> {
+        let mut message = crate::ID.to_bytes().to_vec();
+        self.serialize(&mut message)?;
+        Ok(message)
+    }
+}
+
+pub fn handler(ctx: Context<ExecuteIntent>, intent: SwapIntent, data: Vec<u8>) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp <= intent.deadline,
+        SwapError::IntentExpired
+    );
+
+    verify_intent_signature(
+        &ctx.accounts.instructions_sysvar,
+        ctx.accounts.user.key,
+        &intent.message()?,
+    )?;
+
+    //Consume the nonce so the same intent can't be relayed twice
+    let user_nonce = &mut ctx.accounts.user_nonce;
+    require_eq!(intent.nonce, user_nonce.nonce, SwapError::InvalidNonce);
+    user_nonce.user = ctx.accounts.user.key();
+    user_nonce.nonce = user_nonce
+        .nonce
+        .checked_add(1)
+        .ok_or(SwapError::MathOverflow)?;
+    user_nonce.bump = ctx.bumps.user_nonce;
+
+    //PDA signer seeds for the vault
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+    let user_key = ctx.accounts.user.key();
+    let authority_seeds: &[&[&[u8]]] = &[&[
+        INTENT_AUTHORITY_SEED,
+        user_key.as_ref(),
+        &[ctx.bumps.intent_authority],
+    ]];
+
+    //Pull the input from the user, who approves their intent authority as delegate. The vault
+    //signs every route, so it must never hold an allowance over user accounts itself
+    transfer_signed(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.user_input_token_account.to_account_info(),
+        ctx.accounts.vault_input_token_account.to_account_info(),
+        ctx.accounts.intent_authority.to_account_info(),
+        intent.amount,
+        authority_seeds,
+    )?;
+
+    let amount_out = swap_exact_in(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        ctx.remaining_accounts,
+        &ctx.accounts.vault.key(),
+        &mut ctx.accounts.vault_input_token_account,
+        &mut ctx.accounts.vault_output_token_account,
+        intent.amount,
+        data,
+        signer_seeds,
+    )?;
+
+    //Count the output paid out of the vault towards the output mint's volume for this window
+    let config = &ctx.accounts.config;
+    let mint_rate_limit = &mut ctx.accounts.mint_rate_limit;
+    mint_rate_limit.bump = ctx.bumps.mint_rate_limit;
+    mint_rate_limit.record(
+        Clock::get()?.slot,
+        config.rate_limit_window_slots,
+        config.default_mint_cap,
+        amount_out,
+    )?;
+
+    //The relayer is only reimbursed if it passed an output token account to be paid into
+    let relayer_fee = if ctx.accounts.relayer_token_account.is_some() {
+        intent.relayer_fee
+    } else {
+        0
+    };
+    let amount_to_recipient = amount_out
+        .checked_sub(relayer_fee)
+        .ok_or(SwapError::SlippageExceeded)?;
+    require_gte!(
+        amount_to_recipient,
+        intent.min_out,
+        SwapError::SlippageExceeded
+    );
+
+    if let Some(relayer_token_account) = &ctx.accounts.relayer_token_account {
+        if relayer_fee > 0 {
+            transfer_signed(
+                &ctx.accounts.output_mint_token_program,
+                &ctx.accounts.output_mint,
+                ctx.accounts.vault_output_token_account.to_account_info(),
+                relayer_token_account.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                relayer_fee,
+                signer_seeds,
+            )?;
+        }
+    }
+
+    transfer_signed(
+        &ctx.accounts.output_mint_token_program,
+        &ctx.accounts.output_mint,
+        ctx.accounts.vault_output_token_account.to_account_info(),
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        amount_to_recipient,
+        signer_seeds,
+    )?;
+
+    msg!(
+        "Intent {} executed: {} out, {} to recipient, {} to relayer",
+        intent.nonce,
+        amount_out,
+        amount_to_recipient,
+        relayer_fee
+    );
+
+    Ok(())
+}
+
+/// Checks that the instruction right before this one is an ed25519 precompile instruction
+/// verifying `signer`'s signature over `message`. The precompile has already rejected the
+/// transaction if the signature itself is invalid, so we only need to check what it covered.
+fn verify_intent_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require_gt!(current_index, 0, SwapError::InvalidSignatureInstruction);
+    let ix = load_instruction_at_checked(usize::from(current_index - 1), instructions_sysvar)?;
+
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        SwapError::InvalidSignatureInstruction
+    );
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LEN && data[0] == 1,
+        SwapError::InvalidSignatureInstruction
+    );
+
+    let read_u16 = |index: usize| {
+        let offset = ED25519_OFFSETS_START + index * 2;
+        u16::from_le_bytes([data[offset], data[offset + 1]])
+    };
+    let signature_instruction_index = read_u16(1);
+    let public_key_offset = usize::from(read_u16(2));
+    let public_key_instruction_index = read_u16(3);
+    let message_offset = usize::from(read_u16(4));
+    let message_size = usize::from(read_u16(5));
+    let message_instruction_index = read_u16(6);
+
+    //Everything has to live inside the precompile instruction itself
+    require!(
+        signature_instruction_index == ED25519_CURRENT_INSTRUCTION
+            && public_key_instruction_index == ED25519_CURRENT_INSTRUCTION
+            && message_instruction_index == ED25519_CURRENT_INSTRUCTION,
+        SwapError::InvalidSignatureInstruction
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + ED25519_PUBKEY_LEN)
+        .ok_or(SwapError::InvalidSignatureInstruction)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(SwapError::InvalidSignatureInstruction)?;
+
+    require!(
+        public_key == signer.as_ref() && signed_message == message,
+        SwapError::SignatureMismatch
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(intent: SwapIntent)]
+pub struct ExecuteIntent<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: the intent signer, verified against the ed25519 instruction in the handler
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer=relayer,
+      space=8 + UserNonce::INIT_SPACE,
+      seeds=[NONCE_SEED, user.key().as_ref()],
+      bump
+    )]
+    pub user_nonce: Account<'info, UserNonce>,
+
+    #[account(address=intent.input_mint @ SwapError::IntentMismatch)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    #[account(address=intent.output_mint @ SwapError::IntentMismatch)]
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=user,
+      token::token_program=input_mint_token_program,
+    )]
+    pub user_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the user's delegate for intents, a PDA that only ever signs the input pull
+    #[account(
+      seeds=[INTENT_AUTHORITY_SEED, user.key().as_ref()],
+      bump
+    )]
+    pub intent_authority: UncheckedAccount<'info>,
+
+    #[account(
+      mut,
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub vault_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub vault_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint=output_mint,
+        associated_token::authority=recipient,
+        associated_token::token_program=output_mint_token_program,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address=intent.recipient @ SwapError::IntentMismatch)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      token::mint=output_mint,
+      token::authority=relayer,
+      token::token_program=output_mint_token_program,
+    )]
+    pub relayer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: address checked, read to find the ed25519 signature instruction
+    #[account(address=instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+    #[account(
+      init_if_needed,
+      payer=relayer,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[MINT_RATE_LIMIT_SEED, vault.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_rate_limit: Box<Account<'info, RateLimit>>,
+    pub system_program: Program<'info, System>,
+}
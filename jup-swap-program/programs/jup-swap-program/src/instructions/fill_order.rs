@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Config, Order, RateLimit};
+use crate::utils::{close_token_account_signed, swap_exact_in, transfer_signed};
+use crate::{CONFIG_SEED, MINT_RATE_LIMIT_SEED, ORDER_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<FillOrder>, data: Vec<u8>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    require_gt!(
+        order.expiry,
+        Clock::get()?.unix_timestamp,
+        SwapError::OrderExpired
+    );
+
+    let owner_key = order.owner;
+    let id_bytes = order.id.to_le_bytes();
+    let order_seeds: &[&[&[u8]]] = &[&[ORDER_SEED, owner_key.as_ref(), &id_bytes, &[order.bump]]];
+    let vault_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+    //Move the escrowed input into the vault, which is the user the route was quoted for
+    transfer_signed(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.vault_input_token_account.to_account_info(),
+        order.to_account_info(),
+        order.amount_in,
+        order_seeds,
+    )?;
+    close_token_account_signed(
+        &ctx.accounts.input_mint_token_program,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        order.to_account_info(),
+        order_seeds,
+    )?;
+
+    let amount_out = swap_exact_in(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        ctx.remaining_accounts,
+        &ctx.accounts.vault.key(),
+        &mut ctx.accounts.vault_input_token_account,
+        &mut ctx.accounts.vault_output_token_account,
+        ctx.accounts.order.amount_in,
+        data,
+        vault_seeds,
+    )?;
+
+    //Count the output paid out of the vault towards the output mint's volume for this window
+    let config = &ctx.accounts.config;
+    let mint_rate_limit = &mut ctx.accounts.mint_rate_limit;
+    mint_rate_limit.bump = ctx.bumps.mint_rate_limit;
+    mint_rate_limit.record(
+        Clock::get()?.slot,
+        config.rate_limit_window_slots,
+        config.default_mint_cap,
+        amount_out,
+    )?;
+
+    //Orders fill in full, so meeting the limit price is the same as meeting min_amount_out
+    require_gte!(
+        amount_out,
+        ctx.accounts.order.min_amount_out,
+        SwapError::LimitPriceNotMet
+    );
+
+    transfer_signed(
+        &ctx.accounts.output_mint_token_program,
+        &ctx.accounts.output_mint,
+        ctx.accounts.vault_output_token_account.to_account_info(),
+        ctx.accounts.owner_output_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        amount_out,
+        vault_seeds,
+    )?;
+
+    msg!(
+        "Order {} filled: {} in, {} out",
+        ctx.accounts.order.id,
+        ctx.accounts.order.amount_in,
+        amount_out
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FillOrder<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      close=owner,
+      has_one=owner,
+      has_one=input_mint,
+      has_one=output_mint,
+      seeds=[ORDER_SEED, owner.key().as_ref(), &order.id.to_le_bytes()],
+      bump=order.bump
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=order,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub vault_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub vault_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint=output_mint,
+        associated_token::authority=owner,
+        associated_token::token_program=output_mint_token_program,
+    )]
+    pub owner_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer=keeper,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[MINT_RATE_LIMIT_SEED, vault.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_rate_limit: Box<Account<'info, RateLimit>>,
+    pub system_program: Program<'info, System>,
+}
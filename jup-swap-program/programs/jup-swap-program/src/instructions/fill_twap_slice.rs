@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Config, RateLimit, TwapOrder};
+use crate::utils::{swap_exact_in, transfer_signed};
+use crate::{BPS_DENOMINATOR, CONFIG_SEED, MINT_RATE_LIMIT_SEED, TWAP_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<FillTwapSlice>, data: Vec<u8>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let twap = &ctx.accounts.twap;
+    if twap.slices_filled > 0 {
+        let next_slot = twap
+            .last_fill_slot
+            .checked_add(twap.min_slot_gap)
+            .ok_or(SwapError::MathOverflow)?;
+        require_gte!(slot, next_slot, SwapError::SlotGapNotElapsed);
+    }
+
+    let amount_in = twap.next_slice_amount()?;
+    let base_slice = twap.total_input / u64::from(twap.num_slices);
+    let min_out = u64::try_from(
+        u128::from(twap.limits.min_out_per_slice) * u128::from(amount_in) / u128::from(base_slice),
+    )
+    .map_err(|_| SwapError::MathOverflow)?;
+
+    let owner_key = twap.owner;
+    let id_bytes = twap.id.to_le_bytes();
+    let twap_seeds: &[&[&[u8]]] = &[&[TWAP_SEED, owner_key.as_ref(), &id_bytes, &[twap.bump]]];
+    let vault_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+    transfer_signed(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.vault_input_token_account.to_account_info(),
+        twap.to_account_info(),
+        amount_in,
+        twap_seeds,
+    )?;
+
+    let amount_out = swap_exact_in(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        ctx.remaining_accounts,
+        &ctx.accounts.vault.key(),
+        &mut ctx.accounts.vault_input_token_account,
+        &mut ctx.accounts.vault_output_token_account,
+        amount_in,
+        data,
+        vault_seeds,
+    )?;
+
+    //Count the output paid out of the vault towards the output mint's volume for this window
+    let config = &ctx.accounts.config;
+    let mint_rate_limit = &mut ctx.accounts.mint_rate_limit;
+    mint_rate_limit.bump = ctx.bumps.mint_rate_limit;
+    mint_rate_limit.record(
+        Clock::get()?.slot,
+        config.rate_limit_window_slots,
+        config.default_mint_cap,
+        amount_out,
+    )?;
+    require_gte!(amount_out, min_out, SwapError::SlippageExceeded);
+
+    //Only downside deviation is rejected: the average price across fills, this one included,
+    //must be at least the reference price, reference_out_per_slice / base_slice, less
+    //max_deviation_bps. Slices can't drag the average down one at a time
+    let twap = &ctx.accounts.twap;
+    let filled_input = twap
+        .filled_input
+        .checked_add(amount_in)
+        .ok_or(SwapError::MathOverflow)?;
+    let filled_output = twap
+        .filled_output
+        .checked_add(amount_out)
+        .ok_or(SwapError::MathOverflow)?;
+    let limits = &twap.limits;
+    let average_price = u128::from(filled_output)
+        .checked_mul(u128::from(base_slice))
+        .and_then(|v| v.checked_mul(u128::from(BPS_DENOMINATOR)))
+        .ok_or(SwapError::MathOverflow)?;
+    let min_price = u128::from(limits.reference_out_per_slice)
+        .checked_mul(u128::from(filled_input))
+        .and_then(|v| {
+            v.checked_mul(u128::from(
+                BPS_DENOMINATOR - u64::from(limits.max_deviation_bps),
+            ))
+        })
+        .ok_or(SwapError::MathOverflow)?;
+    require_gte!(average_price, min_price, SwapError::PriceDeviationExceeded);
+
+    transfer_signed(
+        &ctx.accounts.output_mint_token_program,
+        &ctx.accounts.output_mint,
+        ctx.accounts.vault_output_token_account.to_account_info(),
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        amount_out,
+        vault_seeds,
+    )?;
+
+    let twap = &mut ctx.accounts.twap;
+    twap.slices_filled += 1;
+    twap.last_fill_slot = slot;
+    twap.filled_input = filled_input;
+    twap.filled_output = filled_output;
+
+    msg!(
+        "TWAP {} slice {}/{}: {} in, {} out",
+        twap.id,
+        twap.slices_filled,
+        twap.num_slices,
+        amount_in,
+        amount_out
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FillTwapSlice<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+      mut,
+      has_one=recipient,
+      has_one=input_mint,
+      has_one=output_mint,
+      seeds=[TWAP_SEED, twap.owner.as_ref(), &twap.id.to_le_bytes()],
+      bump=twap.bump
+    )]
+    pub twap: Box<Account<'info, TwapOrder>>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=twap,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub vault_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub vault_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint=output_mint,
+        associated_token::authority=recipient,
+        associated_token::token_program=output_mint_token_program,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub recipient: SystemAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer=keeper,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[MINT_RATE_LIMIT_SEED, vault.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_rate_limit: Box<Account<'info, RateLimit>>,
+    pub system_program: Program<'info, System>,
+}
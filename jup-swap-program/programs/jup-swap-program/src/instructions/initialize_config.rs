@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::program::JupSwapProgram;
+use crate::state::Config;
+use crate::CONFIG_SEED;
+
+pub fn handler(ctx: Context<InitializeConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.set_inner(Config::new(ctx.accounts.authority.key(), ctx.bumps.config));
+
+    msg!("Config initialized, authority: {}", config.authority);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    //Only the upgrade authority can create the config so nobody can front run it
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+      init,
+      payer=authority,
+      space=8 + Config::INIT_SPACE,
+      seeds=[CONFIG_SEED],
+      bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(constraint=program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, JupSwapProgram>,
+
+    #[account(
+      constraint=program_data.upgrade_authority_address == Some(authority.key())
+        @ SwapError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,82 @@
+// Every instruction module exposes its own `handler`, they're always called by path
+#![allow(ambiguous_glob_reexports)]
+
+pub mod approve_referrer;
+pub mod cancel_config_change;
+pub mod cancel_order;
+pub mod cancel_trigger_order;
+pub mod cancel_twap;
+pub mod claim_referral;
+pub mod claim_vested;
+pub mod close_dca;
+pub mod close_delegation;
+pub mod close_receipt;
+pub mod close_vault_token_account;
+pub mod create_dca;
+pub mod create_delegation;
+pub mod create_integrator;
+pub mod create_invoice;
+pub mod create_order;
+pub mod create_portfolio;
+pub mod create_trigger_order;
+pub mod create_twap;
+pub mod execute_config_change;
+pub mod execute_dca_cycle;
+pub mod execute_intent;
+pub mod fill_order;
+pub mod fill_twap_slice;
+pub mod initialize_config;
+pub mod pay_invoice;
+pub mod queue_config_change;
+pub mod rebalance_portfolio;
+pub mod set_caller_rate_limit;
+pub mod set_mint_rate_limit;
+pub mod set_portfolio_targets;
+pub mod swap;
+pub mod swap_from_delegate;
+pub mod trigger_order;
+pub mod withdraw_dca;
+pub mod withdraw_integrator_token;
+pub mod withdraw_lamports;
+pub mod withdraw_portfolio_token;
+pub mod withdraw_token;
+
+pub use approve_referrer::*;
+pub use cancel_config_change::*;
+pub use cancel_order::*;
+pub use cancel_trigger_order::*;
+pub use cancel_twap::*;
+pub use claim_referral::*;
+pub use claim_vested::*;
+pub use close_dca::*;
+pub use close_delegation::*;
+pub use close_receipt::*;
+pub use close_vault_token_account::*;
+pub use create_dca::*;
+pub use create_delegation::*;
+pub use create_integrator::*;
+pub use create_invoice::*;
+pub use create_order::*;
+pub use create_portfolio::*;
+pub use create_trigger_order::*;
+pub use create_twap::*;
+pub use execute_config_change::*;
+pub use execute_dca_cycle::*;
+pub use execute_intent::*;
+pub use fill_order::*;
+pub use fill_twap_slice::*;
+pub use initialize_config::*;
+pub use pay_invoice::*;
+pub use queue_config_change::*;
+pub use rebalance_portfolio::*;
+pub use set_caller_rate_limit::*;
+pub use set_mint_rate_limit::*;
+pub use set_portfolio_targets::*;
+pub use swap::*;
+pub use swap_from_delegate::*;
+pub use trigger_order::*;
+pub use withdraw_dca::*;
+pub use withdraw_integrator_token::*;
+pub use withdraw_lamports::*;
+pub use withdraw_portfolio_token::*;
+pub use withdraw_token::*;
@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Config, Invoice, RateLimit};
+use crate::utils::{swap_and_measure, transfer_signed, transfer_tokens};
+use crate::{CONFIG_SEED, INVOICE_SEED, MINT_RATE_LIMIT_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<PayInvoice>, data: Vec<u8>, amount_in: u64) -> Result<()> {
+    require_gt!(amount_in, 0, SwapError::ZeroAmount);
+    require_gt!(
+        ctx.accounts.invoice.expiry,
+        Clock::get()?.unix_timestamp,
+        SwapError::InvoiceExpired
+    );
+
+    //PDA signer seeds for the vault
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+    //Move the payer's input into the vault, which is the user the route was quoted for
+    transfer_tokens(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.payer_input_token_account.to_account_info(),
+        ctx.accounts.vault_input_token_account.to_account_info(),
+        ctx.accounts.payer.to_account_info(),
+        amount_in,
+    )?;
+    ctx.accounts.vault_input_token_account.reload()?;
+    let input_before = ctx.accounts.vault_input_token_account.amount;
+
+    let amount_out = swap_and_measure(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        ctx.remaining_accounts,
+        &ctx.accounts.vault.key(),
+        &mut ctx.accounts.vault_output_token_account,
+        data,
+        signer_seeds,
+    )?;
+
+    //Count the output paid out of the vault towards the output mint's volume for this window
+    let config = &ctx.accounts.config;
+    let mint_rate_limit = &mut ctx.accounts.mint_rate_limit;
+    mint_rate_limit.bump = ctx.bumps.mint_rate_limit;
+    mint_rate_limit.record(
+        Clock::get()?.slot,
+        config.rate_limit_window_slots,
+        config.default_mint_cap,
+        amount_out,
+    )?;
+    ctx.accounts.vault_input_token_account.reload()?;
+    let input_spent = input_before
+        .checked_sub(ctx.accounts.vault_input_token_account.amount)
+        .ok_or(SwapError::MathOverflow)?;
+
+    let invoice_amount = ctx.accounts.invoice.amount;
+    let surplus = amount_out
+        .checked_sub(invoice_amount)
+        .ok_or(SwapError::SlippageExceeded)?;
+    //The route can't take more than the payer put in for it
+    let unspent_input = amount_in
+        .checked_sub(input_spent)
+        .ok_or(SwapError::InsufficientInput)?;
+
+    transfer_signed(
+        &ctx.accounts.output_mint_token_program,
+        &ctx.accounts.output_mint,
+        ctx.accounts.vault_output_token_account.to_account_info(),
+        ctx.accounts.merchant_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        invoice_amount,
+        signer_seeds,
+    )?;
+
+    //Refund whatever the route delivered above the invoice amount
+    if surplus > 0 {
+        transfer_signed(
+            &ctx.accounts.output_mint_token_program,
+            &ctx.accounts.output_mint,
+            ctx.accounts.vault_output_token_account.to_account_info(),
+            ctx.accounts.payer_output_token_account.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            surplus,
+            signer_seeds,
+        )?;
+    }
+
+    //And any input it didn't use
+    if unspent_input > 0 {
+        transfer_signed(
+            &ctx.accounts.input_mint_token_program,
+            &ctx.accounts.input_mint,
+            ctx.accounts.vault_input_token_account.to_account_info(),
+            ctx.accounts.payer_input_token_account.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            unspent_input,
+            signer_seeds,
+        )?;
+    }
+
+    let invoice = &mut ctx.accounts.invoice;
+    invoice.paid = true;
+
+    msg!(
+        "Invoice {} paid: {} in, {} to merchant, {} refunded",
+        invoice.id,
+        input_spent,
+        invoice_amount,
+        surplus
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PayInvoice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub merchant: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      has_one=merchant,
+      has_one=output_mint,
+      constraint=!invoice.paid @ SwapError::InvoiceAlreadyPaid,
+      seeds=[INVOICE_SEED, merchant.key().as_ref(), &invoice.id.to_le_bytes()],
+      bump=invoice.bump
+    )]
+    pub invoice: Box<Account<'info, Invoice>>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=payer,
+      token::token_program=input_mint_token_program,
+    )]
+    pub payer_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      init_if_needed,
+      payer=payer,
+      associated_token::mint=output_mint,
+      associated_token::authority=payer,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub payer_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub vault_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub vault_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      init_if_needed,
+      payer=payer,
+      associated_token::mint=output_mint,
+      associated_token::authority=merchant,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub merchant_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+    #[account(
+      init_if_needed,
+      payer=payer,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[MINT_RATE_LIMIT_SEED, vault.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_rate_limit: Box<Account<'info, RateLimit>>,
+    pub system_program: Program<'info, System>,
+}
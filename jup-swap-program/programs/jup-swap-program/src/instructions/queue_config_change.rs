@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::state::{Config, ConfigChange, Integrator, PendingChange};
+use crate::{CONFIG_SEED, PENDING_CHANGE_SEED};
+
+pub fn handler(ctx: Context<QueueConfigChange>, id: u64, change: ConfigChange) -> Result<()> {
+    change.validate()?;
+    let config = &ctx.accounts.config;
+    authorize(
+        config,
+        ctx.accounts.integrator.as_deref(),
+        &change,
+        ctx.accounts.proposer.key,
+    )?;
+
+    let eta = Clock::get()?
+        .unix_timestamp
+        .checked_add(config.timelock_delay)
+        .ok_or(SwapError::MathOverflow)?;
+
+    let pending = &mut ctx.accounts.pending_change;
+    pending.proposer = ctx.accounts.proposer.key();
+    pending.id = id;
+    pending.change = change;
+    pending.eta = eta;
+    pending.bump = ctx.bumps.pending_change;
+
+    msg!(
+        "Config change {} queued, executable from {}: {:?}",
+        id,
+        eta,
+        pending.change
+    );
+
+    Ok(())
+}
+
+/// Config changes are the config authority's to make, integrator changes the integrator's.
+pub fn authorize(
+    config: &Config,
+    integrator: Option<&Account<Integrator>>,
+    change: &ConfigChange,
+    proposer: &Pubkey,
+) -> Result<()> {
+    let authority = match change.integrator_id() {
+        Some(id) => {
+            let integrator = integrator.ok_or(SwapError::Unauthorized)?;
+            require_eq!(integrator.id, id, SwapError::Unauthorized);
+            integrator.authority
+        }
+        None => config.authority,
+    };
+    require_keys_eq!(authority, *proposer, SwapError::Unauthorized);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// Only for integrator changes
+    pub integrator: Option<Box<Account<'info, Integrator>>>,
+
+    #[account(
+      init,
+      payer=proposer,
+      space=8 + PendingChange::INIT_SPACE,
+      seeds=[PENDING_CHANGE_SEED, proposer.key().as_ref(), &id.to_le_bytes()],
+      bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    pub system_program: Program<'info, System>,
+}
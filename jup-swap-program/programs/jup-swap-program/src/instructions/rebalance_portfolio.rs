@@ -0,0 +1,296 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Owners;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::oracle::{check_oracle_rate, OraclePrice};
+use crate::state::{Config, Portfolio};
+use crate::utils::swap_and_measure;
+use crate::{BPS_DENOMINATOR, CONFIG_SEED, PORTFOLIO_SEED};
+
+/// A portfolio asset's token account along with what it takes to value its balance.
+struct AssetQuote<'a, 'info> {
+    mint: Pubkey,
+    token_account: &'a AccountInfo<'info>,
+    decimals: u8,
+    price: OraclePrice,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RebalancePortfolio<'info>>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let portfolio = &ctx.accounts.portfolio;
+    let input_mint = ctx.accounts.input_mint.key();
+    let output_mint = ctx.accounts.output_mint.key();
+    require!(
+        input_mint != output_mint && portfolio.holds(&input_mint) && portfolio.holds(&output_mint),
+        SwapError::InvalidPortfolioAccounts
+    );
+
+    //The first remaining accounts are [mint, token account, price update] for each asset in
+    //target order, the rest is the route
+    let asset_accounts_len = portfolio.assets.len() * 3;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        asset_accounts_len,
+        SwapError::InvalidPortfolioAccounts
+    );
+    let (asset_accounts, route_accounts) = ctx.remaining_accounts.split_at(asset_accounts_len);
+    let quotes = load_quotes(
+        portfolio,
+        &portfolio.key(),
+        asset_accounts,
+        &ctx.accounts.config,
+        Clock::get()?.unix_timestamp,
+    )?;
+    let balances_before = balances(&quotes)?;
+    let values_before = asset_values(&quotes, &balances_before)?;
+    let distance_before = distance_from_targets(portfolio, &values_before)?;
+
+    let authority_key = portfolio.authority;
+    let id_bytes = portfolio.id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        PORTFOLIO_SEED,
+        authority_key.as_ref(),
+        &id_bytes,
+        &[portfolio.bump],
+    ]];
+
+    //The portfolio is the user the route was quoted for
+    let input_before = ctx.accounts.portfolio_input_token_account.amount;
+    let amount_out = swap_and_measure(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        route_accounts,
+        &portfolio.key(),
+        &mut ctx.accounts.portfolio_output_token_account,
+        data,
+        signer_seeds,
+    )?;
+    ctx.accounts.portfolio_input_token_account.reload()?;
+    let amount_in = input_before
+        .checked_sub(ctx.accounts.portfolio_input_token_account.amount)
+        .ok_or(SwapError::MathOverflow)?;
+    require_gt!(amount_in, 0, SwapError::ZeroAmount);
+
+    let quote = |mint: &Pubkey| {
+        quotes
+            .iter()
+            .find(|quote| quote.mint == *mint)
+            .ok_or(SwapError::InvalidPortfolioAccounts)
+    };
+    let (input_quote, output_quote) = (quote(&input_mint)?, quote(&output_mint)?);
+    check_oracle_rate(
+        amount_in,
+        input_quote.decimals,
+        &input_quote.price,
+        amount_out,
+        output_quote.decimals,
+        &output_quote.price,
+        portfolio.max_slippage_bps,
+    )?;
+
+    //The route signs as the portfolio, so it must not touch any holding but the input
+    let balances_after = balances(&quotes)?;
+    for (quote, (before, after)) in quotes
+        .iter()
+        .zip(balances_before.iter().zip(&balances_after))
+    {
+        if quote.mint != input_mint {
+            require_gte!(*after, *before, SwapError::PortfolioBalanceDecreased);
+        }
+    }
+
+    //Nor lose more of the portfolio's total value than its slippage allows
+    let values_after = asset_values(&quotes, &balances_after)?;
+    let total_before = total_value(&values_before)?;
+    let total_after = total_value(&values_after)?;
+    require!(
+        total_after
+            .checked_mul(u128::from(BPS_DENOMINATOR))
+            .ok_or(SwapError::MathOverflow)?
+            >= total_before
+                .checked_mul(u128::from(
+                    BPS_DENOMINATOR - u64::from(portfolio.max_slippage_bps)
+                ))
+                .ok_or(SwapError::MathOverflow)?,
+        SwapError::PortfolioValueLost
+    );
+
+    let distance_after = distance_from_targets(portfolio, &values_after)?;
+    require_gt!(
+        distance_before,
+        distance_after,
+        SwapError::RebalanceNotImproving
+    );
+
+    msg!(
+        "Portfolio {} rebalanced: {} of {} for {} of {}",
+        portfolio.id,
+        amount_in,
+        input_mint,
+        amount_out,
+        output_mint
+    );
+
+    Ok(())
+}
+
+fn load_quotes<'a, 'info>(
+    portfolio: &Portfolio,
+    portfolio_key: &Pubkey,
+    asset_accounts: &'a [AccountInfo<'info>],
+    config: &Config,
+    now: i64,
+) -> Result<Vec<AssetQuote<'a, 'info>>> {
+    portfolio
+        .assets
+        .iter()
+        .zip(asset_accounts.chunks_exact(3))
+        .map(|(asset, accounts)| {
+            let (mint_info, token_account, price_update) =
+                (&accounts[0], &accounts[1], &accounts[2]);
+            require!(
+                *mint_info.key == asset.mint && Mint::owners().contains(mint_info.owner),
+                SwapError::InvalidPortfolioAccounts
+            );
+            let mint = Mint::try_deserialize(&mut &mint_info.try_borrow_data()?[..])?;
+            //Only the portfolio's ATA counts towards its holdings
+            require_keys_eq!(
+                *token_account.key,
+                get_associated_token_address_with_program_id(
+                    portfolio_key,
+                    &asset.mint,
+                    mint_info.owner
+                ),
+                SwapError::InvalidPortfolioAccounts
+            );
+            require_keys_eq!(
+                *token_account.owner,
+                *mint_info.owner,
+                SwapError::InvalidPortfolioAccounts
+            );
+
+            let price = OraclePrice::load(price_update)?;
+            require!(
+                config.oracle_feed_id(&asset.mint) == Some(price.feed_id),
+                SwapError::OracleFeedMismatch
+            );
+            price.require_fresh(now, config.oracle_max_age)?;
+
+            Ok(AssetQuote {
+                mint: asset.mint,
+                token_account,
+                decimals: mint.decimals,
+                price,
+            })
+        })
+        .collect()
+}
+
+/// The assets' token account balances as they are now, in quote order.
+fn balances(quotes: &[AssetQuote]) -> Result<Vec<u64>> {
+    quotes
+        .iter()
+        .map(|quote| {
+            let data = quote.token_account.try_borrow_data()?;
+            Ok(TokenAccount::try_deserialize(&mut &data[..])?.amount)
+        })
+        .collect()
+}
+
+/// Value of each asset's balance at the quotes' prices, all in the same unit so they can be
+/// added up and compared across balances valued with the same quotes.
+fn asset_values(quotes: &[AssetQuote], balances: &[u64]) -> Result<Vec<u128>> {
+    //Each value as an integer times 10^exponent, then brought to the smallest exponent
+    let mut values = Vec::with_capacity(quotes.len());
+    for (quote, balance) in quotes.iter().zip(balances) {
+        let value = u128::from(*balance)
+            .checked_mul(quote.price.price as u128)
+            .ok_or(SwapError::MathOverflow)?;
+        let exponent = i64::from(quote.price.exponent) - i64::from(quote.decimals);
+        values.push((value, exponent));
+    }
+    let min_exponent = values
+        .iter()
+        .map(|(_, exponent)| *exponent)
+        .min()
+        .unwrap_or(0);
+    values
+        .into_iter()
+        .map(|(value, exponent)| {
+            u32::try_from(exponent - min_exponent)
+                .ok()
+                .and_then(|by| 10u128.checked_pow(by))
+                .and_then(|factor| value.checked_mul(factor))
+                .ok_or(SwapError::MathOverflow.into())
+        })
+        .collect()
+}
+
+fn total_value(values: &[u128]) -> Result<u128> {
+    values
+        .iter()
+        .try_fold(0u128, |total, value| total.checked_add(*value))
+        .ok_or(SwapError::MathOverflow.into())
+}
+
+/// Sum over the assets of how far their share of the portfolio's value is from their target,
+/// in units of the portfolio's value times 10000.
+fn distance_from_targets(portfolio: &Portfolio, values: &[u128]) -> Result<u128> {
+    let total = total_value(values)?;
+    portfolio
+        .assets
+        .iter()
+        .zip(values)
+        .try_fold(0u128, |distance, (asset, value)| {
+            let actual = value.checked_mul(u128::from(BPS_DENOMINATOR))?;
+            let target = total.checked_mul(u128::from(asset.target_bps))?;
+            distance.checked_add(actual.abs_diff(target))
+        })
+        .ok_or(SwapError::MathOverflow.into())
+}
+
+#[derive(Accounts)]
+pub struct RebalancePortfolio<'info> {
+    pub keeper: Signer<'info>,
+
+    #[account(
+      seeds=[PORTFOLIO_SEED, portfolio.authority.as_ref(), &portfolio.id.to_le_bytes()],
+      bump=portfolio.bump
+    )]
+    pub portfolio: Box<Account<'info, Portfolio>>,
+
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=portfolio,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub portfolio_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=portfolio,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub portfolio_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+}
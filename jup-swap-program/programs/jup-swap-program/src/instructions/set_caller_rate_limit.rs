@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::SwapError;
+use crate::instructions::execute_config_change::require_executable;
+use crate::state::{Config, ConfigChange, PendingChange, RateLimit};
+use crate::{CALLER_RATE_LIMIT_SEED, CONFIG_SEED, PENDING_CHANGE_SEED};
+
+/// Applies a queued [`ConfigChange::CallerRateLimit`], setting the cap of `caller`'s rate
+/// limit on `mint` in `vault`'s swaps. `None` puts it back on the config's default.
+pub fn handler(
+    ctx: Context<SetCallerRateLimit>,
+    vault: Pubkey,
+    caller: Pubkey,
+    cap: Option<u64>,
+) -> Result<()> {
+    let pending = &ctx.accounts.pending_change;
+    require_executable(pending, &ctx.accounts.config, None)?;
+    require!(
+        pending.change
+            == ConfigChange::CallerRateLimit {
+                vault,
+                caller,
+                mint: ctx.accounts.mint.key(),
+                cap,
+            },
+        SwapError::PendingChangeMismatch
+    );
+
+    let rate_limit = &mut ctx.accounts.caller_rate_limit;
+    rate_limit.cap = cap;
+    rate_limit.bump = ctx.bumps.caller_rate_limit;
+
+    msg!(
+        "Rate limit cap of {} on {} in vault {} set to {:?}",
+        caller,
+        ctx.accounts.mint.key(),
+        vault,
+        cap
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(vault: Pubkey, caller: Pubkey)]
+pub struct SetCallerRateLimit<'info> {
+    /// Anyone, pays for the rate limit if it doesn't exist yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: receives the pending change's rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+      init_if_needed,
+      payer=payer,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[
+        CALLER_RATE_LIMIT_SEED,
+        vault.as_ref(),
+        caller.as_ref(),
+        mint.key().as_ref()
+      ],
+      bump
+    )]
+    pub caller_rate_limit: Account<'info, RateLimit>,
+
+    #[account(
+      mut,
+      close=proposer,
+      has_one=proposer,
+      seeds=[PENDING_CHANGE_SEED, proposer.key().as_ref(), &pending_change.id.to_le_bytes()],
+      bump=pending_change.bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    pub system_program: Program<'info, System>,
+}
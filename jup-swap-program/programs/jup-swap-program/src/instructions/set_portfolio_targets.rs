@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::state::{Portfolio, PortfolioAsset};
+use crate::PORTFOLIO_SEED;
+
+pub fn handler(
+    ctx: Context<SetPortfolioTargets>,
+    assets: Vec<PortfolioAsset>,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    Portfolio::validate_targets(&assets, max_slippage_bps)?;
+
+    let portfolio = &mut ctx.accounts.portfolio;
+    portfolio.assets = assets;
+    portfolio.max_slippage_bps = max_slippage_bps;
+
+    msg!(
+        "Portfolio {} targets set: {} assets, {} bps max slippage",
+        portfolio.id,
+        portfolio.assets.len(),
+        max_slippage_bps
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPortfolioTargets<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      mut,
+      has_one=authority @ SwapError::Unauthorized,
+      seeds=[PORTFOLIO_SEED, authority.key().as_ref(), &portfolio.id.to_le_bytes()],
+      bump=portfolio.bump
+    )]
+    pub portfolio: Account<'info, Portfolio>,
+}
@@ -0,0 +1,664 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::mint_risks::mint_risks;
+use crate::oracle::{check_oracle_rate, OraclePrice};
+use crate::state::{Config, Integrator, MintStats, RateLimit, Receipt, Referrer, Vesting};
+use crate::utils::{invoke_memo, swap_and_measure, transfer_signed};
+use crate::{
+    memo_program_id, BPS_DENOMINATOR, CALLER_RATE_LIMIT_SEED, CONFIG_SEED, INTEGRATOR_SEED,
+    MINT_RATE_LIMIT_SEED, MINT_STATS_SEED, RECEIPT_SEED, REFERRER_SEED, VAULT_SEED,
+    VESTING_ESCROW_SEED, VESTING_SEED,
+};
+
+/// Longest memo `swap` attaches to its payout.
+pub const MAX_MEMO_LEN: usize = 256;
+
+/// Instruction for an allowlisted program to run right after the payout, so integrators can
+/// e.g. deposit the output somewhere in the same transaction. The last `accounts_len`
+/// remaining accounts belong to it instead of the Jupiter route, and the program gets called
+/// with the token account paid into (the recipient's, or the vesting escrow) appended to them
+/// and the paid amount (u64 LE) appended to `data`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapCallback {
+    pub data: Vec<u8>,
+    pub accounts_len: u8,
+}
+
+/// Locks the payout in a vesting escrow for the recipient instead of paying it out, see
+/// [`Vesting`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    /// Recipient chosen id of the vesting account
+    pub id: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+}
+
+/// What a swap did, returned through `set_return_data` so programs calling `swap` over CPI can
+/// read it back with `get_return_data`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapResult {
+    /// Input the route took out of the vault
+    pub amount_in: u64,
+    /// Output the route delivered to the vault
+    pub amount_out: u64,
+    /// Output paid to the recipient
+    pub amount_paid: u64,
+    /// Output kept as fees
+    pub fee: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+    data: Vec<u8>,
+    amount: u64,
+    callback: Option<SwapCallback>,
+    receipt_id: Option<u64>,
+    vesting_schedule: Option<VestingSchedule>,
+    memo: Option<String>,
+) -> Result<SwapResult> {
+    require!(
+        receipt_id.is_some() == ctx.accounts.receipt.is_some(),
+        SwapError::ReceiptMismatch
+    );
+    require!(
+        vesting_schedule.is_some() == ctx.accounts.vesting.is_some(),
+        SwapError::VestingMismatch
+    );
+
+    //Integrator swaps run out of the integrator's own vault, everything else out of the shared one
+    let integrator_key = ctx
+        .accounts
+        .integrator
+        .as_ref()
+        .map(|integrator| integrator.key());
+    let mut vault_seeds: Vec<&[u8]> = vec![VAULT_SEED];
+    if let Some(integrator_key) = &integrator_key {
+        vault_seeds.push(integrator_key.as_ref());
+    }
+    let (vault_key, vault_bump) = Pubkey::find_program_address(&vault_seeds, ctx.program_id);
+    require_keys_eq!(ctx.accounts.vault.key(), vault_key, SwapError::InvalidVault);
+    let vault_bump = [vault_bump];
+    vault_seeds.push(&vault_bump);
+
+    //PDA signer seeds for the vault
+    let signer_seeds: &[&[&[u8]]] = &[&vault_seeds];
+
+    if let Some(integrator) = &ctx.accounts.integrator {
+        require!(
+            integrator.allows_mint(&ctx.accounts.input_mint.key())
+                && integrator.allows_mint(&ctx.accounts.output_mint.key()),
+            SwapError::MintNotAllowed
+        );
+        require!(
+            integrator.allows_relayer(ctx.accounts.payer.key),
+            SwapError::RelayerNotAllowed
+        );
+    }
+
+    //Refuse to pay out in mints that could trap or claw back the recipient's tokens
+    let output_mint_key = ctx.accounts.output_mint.key();
+    if !ctx.accounts.config.trusted_mints.contains(&output_mint_key) {
+        let risks = mint_risks(&ctx.accounts.output_mint.to_account_info())?
+            & ctx.accounts.config.blocked_mint_risks;
+        if risks != 0 {
+            msg!(
+                "Output mint {} has blocked risk flags {:#06b}",
+                output_mint_key,
+                risks
+            );
+            return err!(SwapError::DangerousMint);
+        }
+    }
+
+    let callback_accounts_len = callback
+        .as_ref()
+        .map_or(0, |callback| usize::from(callback.accounts_len));
+    let route_accounts_len = ctx
+        .remaining_accounts
+        .len()
+        .checked_sub(callback_accounts_len)
+        .ok_or(SwapError::InvalidCallbackAccounts)?;
+    let (route_accounts, callback_accounts) = ctx.remaining_accounts.split_at(route_accounts_len);
+
+    //invoke the cpi call to jupiter program
+    let input_before = ctx.accounts.vault_input_token_account.amount;
+    let amount_out = swap_and_measure(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        route_accounts,
+        &ctx.accounts.vault.key(),
+        &mut ctx.accounts.vault_output_token_account,
+        data,
+        signer_seeds,
+    )?;
+    ctx.accounts.vault_input_token_account.reload()?;
+    let amount_in = input_before
+        .checked_sub(ctx.accounts.vault_input_token_account.amount)
+        .ok_or(SwapError::MathOverflow)?;
+    //Count the payout towards the output mint's and the caller's volume for this window
+    let config = &ctx.accounts.config;
+    let slot = Clock::get()?.slot;
+    let mint_rate_limit = &mut ctx.accounts.mint_rate_limit;
+    mint_rate_limit.bump = ctx.bumps.mint_rate_limit;
+    mint_rate_limit.record(
+        slot,
+        config.rate_limit_window_slots,
+        config.default_mint_cap,
+        amount,
+    )?;
+    let caller_rate_limit = &mut ctx.accounts.caller_rate_limit;
+    caller_rate_limit.bump = ctx.bumps.caller_rate_limit;
+    caller_rate_limit.record(
+        slot,
+        config.rate_limit_window_slots,
+        config.default_caller_cap,
+        amount,
+    )?;
+
+    //Sanity check the realised rate when the caller passed oracle prices for both mints
+    match (
+        &ctx.accounts.input_price_update,
+        &ctx.accounts.output_price_update,
+    ) {
+        (Some(input_price_update), Some(output_price_update)) => check_against_oracles(
+            &ctx.accounts.config,
+            &ctx.accounts.input_mint,
+            input_price_update,
+            amount_in,
+            &ctx.accounts.output_mint,
+            output_price_update,
+            amount_out,
+        )?,
+        (None, None) => {}
+        _ => return err!(SwapError::OracleMismatch),
+    }
+
+    //Pay into the vesting escrow in vesting mode, to the recipient otherwise
+    let payout_token_account = match (&mut ctx.accounts.vesting, vesting_schedule) {
+        (Some(vesting), Some(schedule)) => {
+            require!(
+                schedule.start <= schedule.cliff
+                    && schedule.cliff <= schedule.end
+                    && schedule.start < schedule.end,
+                SwapError::InvalidVestingSchedule
+            );
+            vesting.recipient = ctx.accounts.recipient.key();
+            vesting.id = schedule.id;
+            vesting.mint = ctx.accounts.output_mint.key();
+            vesting.total = amount;
+            vesting.claimed = 0;
+            vesting.start = schedule.start;
+            vesting.cliff = schedule.cliff;
+            vesting.end = schedule.end;
+            vesting.bump = ctx.bumps.vesting.ok_or(SwapError::VestingMismatch)?;
+            ctx.accounts
+                .vesting_escrow
+                .as_ref()
+                .ok_or(SwapError::VestingMismatch)?
+                .to_account_info()
+        }
+        _ => ctx
+            .accounts
+            .recipient_token_account
+            .as_ref()
+            .ok_or(SwapError::MissingRecipientTokenAccount)?
+            .to_account_info(),
+    };
+
+    msg!("Transferring tokens...");
+    msg!(
+        "Mint: {}",
+        &ctx.accounts.output_mint.to_account_info().key()
+    );
+    msg!(
+        "From Vault Output Token Account: {}",
+        &ctx.accounts.vault_output_token_account.key()
+    );
+    msg!("To Recipient Token Address: {}", payout_token_account.key());
+    msg!("Vault PDA: {}", ctx.accounts.vault.key());
+    msg!(
+        "Vault Output Token Account Authority: {:?}",
+        ctx.accounts.vault_output_token_account.owner
+    );
+
+    //The memo goes right before the payout, where Token-2022's required memos look for it
+    if let Some(memo) = &memo {
+        require_gte!(MAX_MEMO_LEN, memo.len(), SwapError::MemoTooLong);
+        let memo_program = ctx
+            .accounts
+            .memo_program
+            .as_ref()
+            .ok_or(SwapError::MissingMemoProgram)?;
+        invoke_memo(memo_program, memo)?;
+    }
+
+    //Transfer swapped tokens to recipient
+    transfer_signed(
+        &ctx.accounts.output_mint_token_program,
+        &ctx.accounts.output_mint,
+        ctx.accounts.vault_output_token_account.to_account_info(),
+        payout_token_account.clone(),
+        ctx.accounts.vault.to_account_info(),
+        amount,
+        signer_seeds,
+    )?;
+
+    msg!("Tokens transferred successfully.");
+
+    //Integrators take fee_bps on top of the payout, out of the rest of the swap's output
+    let fee = match &ctx.accounts.integrator {
+        Some(integrator) if integrator.fee_bps > 0 => {
+            let fee = u64::try_from(
+                u128::from(amount)
+                    .checked_mul(u128::from(integrator.fee_bps))
+                    .ok_or(SwapError::MathOverflow)?
+                    / u128::from(BPS_DENOMINATOR),
+            )
+            .map_err(|_| SwapError::MathOverflow)?;
+            require_gte!(
+                amount_out,
+                amount.checked_add(fee).ok_or(SwapError::MathOverflow)?,
+                SwapError::OutputBelowPayout
+            );
+            let fee_treasury_token_account = ctx
+                .accounts
+                .fee_treasury_token_account
+                .as_ref()
+                .ok_or(SwapError::InvalidFeeTreasury)?;
+            require!(
+                fee_treasury_token_account.owner == integrator.fee_treasury
+                    && fee_treasury_token_account.mint == ctx.accounts.output_mint.key(),
+                SwapError::InvalidFeeTreasury
+            );
+
+            //The referrer's share comes out of the fee, the recipient's amount is never touched
+            let referral_fee = match &mut ctx.accounts.referrer_account {
+                Some(referrer_account) => {
+                    require_keys_neq!(
+                        referrer_account.referrer,
+                        ctx.accounts.payer.key(),
+                        SwapError::SelfReferral
+                    );
+                    let referral_fee = u64::try_from(
+                        u128::from(fee)
+                            .checked_mul(u128::from(integrator.referral_share_bps))
+                            .ok_or(SwapError::MathOverflow)?
+                            / u128::from(BPS_DENOMINATOR),
+                    )
+                    .map_err(|_| SwapError::MathOverflow)?;
+                    let referrer_escrow = ctx
+                        .accounts
+                        .referrer_escrow
+                        .as_ref()
+                        .ok_or(SwapError::MissingReferralAccounts)?;
+                    transfer_signed(
+                        &ctx.accounts.output_mint_token_program,
+                        &ctx.accounts.output_mint,
+                        ctx.accounts.vault_output_token_account.to_account_info(),
+                        referrer_escrow.to_account_info(),
+                        ctx.accounts.vault.to_account_info(),
+                        referral_fee,
+                        signer_seeds,
+                    )?;
+
+                    referrer_account.unclaimed = referrer_account
+                        .unclaimed
+                        .checked_add(referral_fee)
+                        .ok_or(SwapError::MathOverflow)?;
+                    referrer_account.total_earned = referrer_account
+                        .total_earned
+                        .checked_add(referral_fee)
+                        .ok_or(SwapError::MathOverflow)?;
+                    referral_fee
+                }
+                None => 0,
+            };
+
+            transfer_signed(
+                &ctx.accounts.output_mint_token_program,
+                &ctx.accounts.output_mint,
+                ctx.accounts.vault_output_token_account.to_account_info(),
+                fee_treasury_token_account.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                fee - referral_fee,
+                signer_seeds,
+            )?;
+            fee
+        }
+        _ => 0,
+    };
+
+    //Whatever the route delivered beyond the payout and the fee goes back to the payer, or to
+    //the config's surplus treasury, rather than staying in the vault. A payout the vault's own
+    //balance topped up leaves no surplus
+    let surplus = amount_out.saturating_sub(amount).saturating_sub(fee);
+    let surplus_owner = ctx
+        .accounts
+        .config
+        .surplus_treasury
+        .unwrap_or(ctx.accounts.payer.key());
+    let surplus_destination = &ctx.accounts.surplus_destination;
+    require!(
+        surplus_destination.owner == surplus_owner && surplus_destination.mint == output_mint_key,
+        SwapError::InvalidSurplusDestination
+    );
+    if surplus > 0 {
+        transfer_signed(
+            &ctx.accounts.output_mint_token_program,
+            &ctx.accounts.output_mint,
+            ctx.accounts.vault_output_token_account.to_account_info(),
+            surplus_destination.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            surplus,
+            signer_seeds,
+        )?;
+        msg!("Surplus of {} sent to {}", surplus, surplus_owner);
+    }
+
+    if let Some(callback) = callback {
+        let callback_program = ctx
+            .accounts
+            .callback_program
+            .as_ref()
+            .ok_or(SwapError::MissingCallbackProgram)?;
+        require!(
+            ctx.accounts
+                .config
+                .callback_programs
+                .contains(callback_program.key),
+            SwapError::CallbackProgramNotAllowed
+        );
+        invoke_callback(
+            callback_program,
+            callback_accounts,
+            payout_token_account,
+            callback.data,
+            amount,
+        )?;
+    }
+
+    let result = SwapResult {
+        amount_in,
+        amount_out,
+        amount_paid: amount,
+        fee,
+    };
+
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    mint_stats.input_mint = ctx.accounts.input_mint.key();
+    mint_stats.output_mint = output_mint_key;
+    mint_stats.bump = ctx.bumps.mint_stats;
+    mint_stats.record(amount_in, amount_out, fee, slot)?;
+
+    if let (Some(receipt), Some(receipt_id)) = (&mut ctx.accounts.receipt, receipt_id) {
+        let clock = Clock::get()?;
+        receipt.payer = ctx.accounts.payer.key();
+        receipt.id = receipt_id;
+        receipt.input_mint = ctx.accounts.input_mint.key();
+        receipt.output_mint = ctx.accounts.output_mint.key();
+        receipt.amount_in = result.amount_in;
+        receipt.amount_out = result.amount_out;
+        receipt.amount_paid = result.amount_paid;
+        receipt.recipient = ctx.accounts.recipient.key();
+        receipt.slot = clock.slot;
+        receipt.created_at = clock.unix_timestamp;
+        receipt.bump = ctx.bumps.receipt.ok_or(SwapError::ReceiptMismatch)?;
+        msg!("Receipt {} created", receipt_id);
+    }
+
+    Ok(result)
+}
+
+/// Checks both price updates are recent ones for the feeds configured for their mints, and that
+/// the swap's rate is within the configured deviation from theirs.
+fn check_against_oracles(
+    config: &Config,
+    input_mint: &InterfaceAccount<Mint>,
+    input_price_update: &AccountInfo,
+    amount_in: u64,
+    output_mint: &InterfaceAccount<Mint>,
+    output_price_update: &AccountInfo,
+    amount_out: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let input_price = OraclePrice::load(input_price_update)?;
+    let output_price = OraclePrice::load(output_price_update)?;
+    for (mint, price) in [
+        (input_mint.key(), &input_price),
+        (output_mint.key(), &output_price),
+    ] {
+        require!(
+            config.oracle_feed_id(&mint) == Some(price.feed_id),
+            SwapError::OracleFeedMismatch
+        );
+        price.require_fresh(now, config.oracle_max_age)?;
+    }
+
+    check_oracle_rate(
+        amount_in,
+        input_mint.decimals,
+        &input_price,
+        amount_out,
+        output_mint.decimals,
+        &output_price,
+        config.oracle_max_deviation_bps,
+    )
+}
+
+/// Calls the callback program without any of our signer seeds, the vault never signs for it.
+fn invoke_callback<'info>(
+    callback_program: &AccountInfo<'info>,
+    callback_accounts: &[AccountInfo<'info>],
+    payout_token_account: AccountInfo<'info>,
+    mut data: Vec<u8>,
+    amount: u64,
+) -> Result<()> {
+    let mut accounts: Vec<AccountMeta> = callback_accounts
+        .iter()
+        .map(|acc| AccountMeta {
+            pubkey: *acc.key,
+            is_signer: acc.is_signer,
+            is_writable: acc.is_writable,
+        })
+        .collect();
+    accounts.push(AccountMeta::new(payout_token_account.key(), false));
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let mut account_infos = callback_accounts.to_vec();
+    account_infos.push(payout_token_account);
+    account_infos.push(callback_program.clone());
+
+    invoke(
+        &Instruction {
+            program_id: *callback_program.key,
+            accounts,
+            data,
+        },
+        &account_infos,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(
+    data: Vec<u8>,
+    amount: u64,
+    callback: Option<SwapCallback>,
+    receipt_id: Option<u64>,
+    vesting_schedule: Option<VestingSchedule>,
+    memo: Option<String>,
+)]
+pub struct Swap<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    /// The shared vault, or the integrator's when one is passed, checked in the handler
+    #[account(mut)]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub vault_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub vault_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint=output_mint,
+        associated_token::authority=recipient,
+        associated_token::token_program=output_mint_token_program,
+    )]
+    pub recipient_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub recipient: SystemAccount<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+      init_if_needed,
+      payer=payer,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[MINT_RATE_LIMIT_SEED, vault.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_rate_limit: Box<Account<'info, RateLimit>>,
+
+    #[account(
+      init_if_needed,
+      payer=payer,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[
+        CALLER_RATE_LIMIT_SEED,
+        vault.key().as_ref(),
+        payer.key().as_ref(),
+        output_mint.key().as_ref()
+      ],
+      bump
+    )]
+    pub caller_rate_limit: Box<Account<'info, RateLimit>>,
+
+    #[account(
+      init_if_needed,
+      payer=payer,
+      space=8 + MintStats::INIT_SPACE,
+      seeds=[MINT_STATS_SEED, input_mint.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    /// CHECK: checked against the config's callback allowlist when a callback is requested
+    #[account(executable)]
+    pub callback_program: Option<UncheckedAccount<'info>>,
+
+    #[account(
+      init,
+      payer=payer,
+      space=8 + Receipt::INIT_SPACE,
+      seeds=[RECEIPT_SEED, payer.key().as_ref(), &receipt_id.unwrap_or_default().to_le_bytes()],
+      bump
+    )]
+    pub receipt: Option<Account<'info, Receipt>>,
+
+    #[account(
+      init,
+      payer=payer,
+      space=8 + Vesting::INIT_SPACE,
+      seeds=[
+        VESTING_SEED,
+        recipient.key().as_ref(),
+        &vesting_schedule.map_or(0, |schedule| schedule.id).to_le_bytes()
+      ],
+      bump
+    )]
+    pub vesting: Option<Box<Account<'info, Vesting>>>,
+
+    #[account(
+      init,
+      payer=payer,
+      seeds=[
+        VESTING_ESCROW_SEED,
+        recipient.key().as_ref(),
+        &vesting_schedule.map_or(0, |schedule| schedule.id).to_le_bytes()
+      ],
+      bump,
+      token::mint=output_mint,
+      token::authority=vesting,
+      token::token_program=output_mint_token_program,
+    )]
+    pub vesting_escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: parsed as a Pyth `PriceUpdateV2` for the input mint's configured feed
+    pub input_price_update: Option<UncheckedAccount<'info>>,
+    /// CHECK: parsed as a Pyth `PriceUpdateV2` for the output mint's configured feed
+    pub output_price_update: Option<UncheckedAccount<'info>>,
+
+    #[account(
+      seeds=[INTEGRATOR_SEED, &integrator.id.to_le_bytes()],
+      bump=integrator.bump
+    )]
+    pub integrator: Option<Box<Account<'info, Integrator>>>,
+    /// Integrator fee destination, owned by its `fee_treasury`
+    #[account(mut)]
+    pub fee_treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Wallet referring the swap, paid a share of the integrator's fee
+    pub referrer: Option<SystemAccount<'info>>,
+    /// Only exists once the integrator approved the referrer, see `approve_referrer`
+    #[account(
+      mut,
+      seeds=[
+        REFERRER_SEED,
+        integrator.as_ref().map_or(Pubkey::default(), |integrator| integrator.key()).as_ref(),
+        referrer.as_ref().map_or(Pubkey::default(), |referrer| referrer.key()).as_ref(),
+        output_mint.key().as_ref()
+      ],
+      bump=referrer_account.bump
+    )]
+    pub referrer_account: Option<Box<Account<'info, Referrer>>>,
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=referrer_account,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub referrer_escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Only needed when a memo is attached
+    #[account(address=memo_program_id())]
+    pub memo_program: Option<UncheckedAccount<'info>>,
+
+    /// Output account of the payer, or of the config's surplus treasury, receiving whatever the
+    /// route delivered beyond the payout and the fee.
+    #[account(mut)]
+    pub surplus_destination: Box<InterfaceAccount<'info, TokenAccount>>,
+}
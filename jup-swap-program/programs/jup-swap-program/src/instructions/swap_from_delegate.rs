@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::{Config, Delegation, RateLimit};
+use crate::utils::{swap_exact_in, transfer_signed};
+use crate::{CONFIG_SEED, DELEGATION_SEED, MINT_RATE_LIMIT_SEED, VAULT_SEED};
+
+pub fn handler(
+    ctx: Context<SwapFromDelegate>,
+    data: Vec<u8>,
+    amount_in: u64,
+    min_out: u64,
+) -> Result<()> {
+    let delegation = &ctx.accounts.delegation;
+    //The owner's rate bounds every swap, the keeper's min_out can only tighten it
+    let rate_min_out = u64::try_from(
+        u128::from(delegation.min_out_per_rate_in) * u128::from(amount_in)
+            / u128::from(delegation.rate_in),
+    )
+    .map_err(|_| SwapError::MathOverflow)?;
+    let owner_input_token_account = &ctx.accounts.owner_input_token_account;
+    require!(
+        owner_input_token_account.delegate == Some(delegation.key()).into()
+            && owner_input_token_account.delegated_amount >= amount_in,
+        SwapError::DelegateNotApproved
+    );
+
+    //PDA signer seeds for the vault
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+    let delegation_seeds: &[&[&[u8]]] = &[&[
+        DELEGATION_SEED,
+        delegation.input_token_account.as_ref(),
+        &[delegation.bump],
+    ]];
+
+    //Pull the input as the owner's delegate, this also draws down the approved cap. The
+    //delegation never signs the route, only this capped pull
+    transfer_signed(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        owner_input_token_account.to_account_info(),
+        ctx.accounts.vault_input_token_account.to_account_info(),
+        delegation.to_account_info(),
+        amount_in,
+        delegation_seeds,
+    )?;
+
+    let amount_out = swap_exact_in(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        ctx.remaining_accounts,
+        &ctx.accounts.vault.key(),
+        &mut ctx.accounts.vault_input_token_account,
+        &mut ctx.accounts.vault_output_token_account,
+        amount_in,
+        data,
+        signer_seeds,
+    )?;
+
+    //Count the output paid out of the vault towards the output mint's volume for this window
+    let config = &ctx.accounts.config;
+    let mint_rate_limit = &mut ctx.accounts.mint_rate_limit;
+    mint_rate_limit.bump = ctx.bumps.mint_rate_limit;
+    mint_rate_limit.record(
+        Clock::get()?.slot,
+        config.rate_limit_window_slots,
+        config.default_mint_cap,
+        amount_out,
+    )?;
+    require_gte!(
+        amount_out,
+        rate_min_out.max(min_out),
+        SwapError::SlippageExceeded
+    );
+
+    //Output always goes back to the owner, keepers can't redirect it
+    transfer_signed(
+        &ctx.accounts.output_mint_token_program,
+        &ctx.accounts.output_mint,
+        ctx.accounts.vault_output_token_account.to_account_info(),
+        ctx.accounts.owner_output_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        amount_out,
+        signer_seeds,
+    )?;
+
+    msg!("Swapped {} from delegate for {}", amount_in, amount_out);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SwapFromDelegate<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    pub owner: SystemAccount<'info>,
+
+    #[account(
+      has_one=owner,
+      has_one=keeper,
+      has_one=input_mint,
+      has_one=output_mint,
+      seeds=[DELEGATION_SEED, owner_input_token_account.key().as_ref()],
+      bump=delegation.bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub vault_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub vault_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint=output_mint,
+        associated_token::authority=owner,
+        associated_token::token_program=output_mint_token_program,
+    )]
+    pub owner_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer=keeper,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[MINT_RATE_LIMIT_SEED, vault.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_rate_limit: Box<Account<'info, RateLimit>>,
+    pub system_program: Program<'info, System>,
+}
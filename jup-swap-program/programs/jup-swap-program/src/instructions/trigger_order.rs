@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use std::cmp::Ordering;
+
+use crate::error::SwapError;
+use crate::oracle::OraclePrice;
+use crate::state::{Config, RateLimit, TriggerOrder};
+use crate::utils::{close_token_account_signed, swap_exact_in, transfer_signed};
+use crate::{CONFIG_SEED, MINT_RATE_LIMIT_SEED, TRIGGER_ORDER_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<FillTriggerOrder>, data: Vec<u8>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    let trigger = order.trigger;
+
+    let price = OraclePrice::load(&ctx.accounts.price_update)?;
+    require!(
+        price.feed_id == trigger.feed_id,
+        SwapError::OracleFeedMismatch
+    );
+    price.require_fresh(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.config.oracle_max_age,
+    )?;
+    let stop_loss_hit = match trigger.stop_loss_price {
+        Some(stop_loss_price) => {
+            price.cmp_price(stop_loss_price, trigger.exponent)? != Ordering::Greater
+        }
+        None => false,
+    };
+    let take_profit_hit = match trigger.take_profit_price {
+        Some(take_profit_price) => {
+            price.cmp_price(take_profit_price, trigger.exponent)? != Ordering::Less
+        }
+        None => false,
+    };
+    require!(stop_loss_hit || take_profit_hit, SwapError::TriggerNotMet);
+
+    let owner_key = order.owner;
+    let id_bytes = order.id.to_le_bytes();
+    let order_seeds: &[&[&[u8]]] = &[&[
+        TRIGGER_ORDER_SEED,
+        owner_key.as_ref(),
+        &id_bytes,
+        &[order.bump],
+    ]];
+    let vault_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+    //Move the escrowed position into the vault, which is the user the route was quoted for
+    transfer_signed(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.vault_input_token_account.to_account_info(),
+        order.to_account_info(),
+        order.amount_in,
+        order_seeds,
+    )?;
+    close_token_account_signed(
+        &ctx.accounts.input_mint_token_program,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        order.to_account_info(),
+        order_seeds,
+    )?;
+
+    let amount_out = swap_exact_in(
+        &ctx.accounts.config,
+        ctx.accounts.aggregator_program.key,
+        ctx.remaining_accounts,
+        &ctx.accounts.vault.key(),
+        &mut ctx.accounts.vault_input_token_account,
+        &mut ctx.accounts.vault_output_token_account,
+        ctx.accounts.order.amount_in,
+        data,
+        vault_seeds,
+    )?;
+
+    //Count the output paid out of the vault towards the output mint's volume for this window
+    let config = &ctx.accounts.config;
+    let mint_rate_limit = &mut ctx.accounts.mint_rate_limit;
+    mint_rate_limit.bump = ctx.bumps.mint_rate_limit;
+    mint_rate_limit.record(
+        Clock::get()?.slot,
+        config.rate_limit_window_slots,
+        config.default_mint_cap,
+        amount_out,
+    )?;
+    require_gte!(
+        amount_out,
+        ctx.accounts.order.min_amount_out,
+        SwapError::SlippageExceeded
+    );
+
+    transfer_signed(
+        &ctx.accounts.output_mint_token_program,
+        &ctx.accounts.output_mint,
+        ctx.accounts.vault_output_token_account.to_account_info(),
+        ctx.accounts.owner_output_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        amount_out,
+        vault_seeds,
+    )?;
+
+    msg!(
+        "Trigger order {} {} at {}e{}: {} in, {} out",
+        ctx.accounts.order.id,
+        if stop_loss_hit {
+            "stopped out"
+        } else {
+            "took profit"
+        },
+        price.price,
+        price.exponent,
+        ctx.accounts.order.amount_in,
+        amount_out
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FillTriggerOrder<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      close=owner,
+      has_one=owner,
+      has_one=input_mint,
+      has_one=output_mint,
+      seeds=[TRIGGER_ORDER_SEED, owner.key().as_ref(), &order.id.to_le_bytes()],
+      bump=order.bump
+    )]
+    pub order: Box<Account<'info, TriggerOrder>>,
+
+    #[account(
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: parsed as a Pyth `PriceUpdateV2` for the order's feed
+    pub price_update: UncheckedAccount<'info>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+    pub output_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub output_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=order,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub vault_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      associated_token::mint=output_mint,
+      associated_token::authority=vault,
+      associated_token::token_program=output_mint_token_program,
+    )]
+    pub vault_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint=output_mint,
+        associated_token::authority=owner,
+        associated_token::token_program=output_mint_token_program,
+    )]
+    pub owner_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: checked against the config's aggregator allowlist
+    #[account(executable)]
+    pub aggregator_program: UncheckedAccount<'info>,
+
+    #[account(
+      init_if_needed,
+      payer=keeper,
+      space=8 + RateLimit::INIT_SPACE,
+      seeds=[MINT_RATE_LIMIT_SEED, vault.key().as_ref(), output_mint.key().as_ref()],
+      bump
+    )]
+    pub mint_rate_limit: Box<Account<'info, RateLimit>>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::DcaOrder;
+use crate::utils::transfer_signed;
+use crate::DCA_SEED;
+
+pub fn handler(ctx: Context<WithdrawDca>, amount: u64) -> Result<()> {
+    require!(amount > 0, SwapError::ZeroAmount);
+    require_gte!(
+        ctx.accounts.escrow_token_account.amount,
+        amount,
+        SwapError::InsufficientInput
+    );
+
+    let dca = &ctx.accounts.dca;
+    let owner_key = dca.owner;
+    let id_bytes = dca.id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[DCA_SEED, owner_key.as_ref(), &id_bytes, &[dca.bump]]];
+
+    transfer_signed(
+        &ctx.accounts.input_mint_token_program,
+        &ctx.accounts.input_mint,
+        ctx.accounts.escrow_token_account.to_account_info(),
+        ctx.accounts.owner_input_token_account.to_account_info(),
+        dca.to_account_info(),
+        amount,
+        signer_seeds,
+    )?;
+
+    msg!("Withdrew {} of unspent input from DCA {}", amount, dca.id);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawDca<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+      has_one=owner,
+      has_one=input_mint,
+      seeds=[DCA_SEED, owner.key().as_ref(), &dca.id.to_le_bytes()],
+      bump=dca.bump
+    )]
+    pub dca: Account<'info, DcaOrder>,
+
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub input_mint_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=input_mint,
+      associated_token::authority=dca,
+      associated_token::token_program=input_mint_token_program,
+    )]
+    pub escrow_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      token::mint=input_mint,
+      token::authority=owner,
+      token::token_program=input_mint_token_program,
+    )]
+    pub owner_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
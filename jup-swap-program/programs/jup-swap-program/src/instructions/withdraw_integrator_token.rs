@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::Integrator;
+use crate::utils::transfer_signed;
+use crate::{INTEGRATOR_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<WithdrawIntegratorToken>, amount: u64) -> Result<()> {
+    let integrator_key = ctx.accounts.integrator.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, integrator_key.as_ref(), &[ctx.bumps.vault]]];
+
+    transfer_signed(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.vault_token_account.to_account_info(),
+        ctx.accounts.destination_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        amount,
+        signer_seeds,
+    )?;
+
+    msg!(
+        "Withdrew {} of {} from integrator {}'s vault to {}",
+        amount,
+        ctx.accounts.mint.key(),
+        ctx.accounts.integrator.id,
+        ctx.accounts.destination_token_account.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawIntegratorToken<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      has_one=authority @ SwapError::Unauthorized,
+      seeds=[INTEGRATOR_SEED, &integrator.id.to_le_bytes()],
+      bump=integrator.bump
+    )]
+    pub integrator: Account<'info, Integrator>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      seeds=[VAULT_SEED, integrator.key().as_ref()],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=mint,
+      associated_token::authority=vault,
+      associated_token::token_program=token_program,
+    )]
+    pub vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      token::mint=mint,
+      token::token_program=token_program,
+    )]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::error::SwapError;
+use crate::state::Config;
+use crate::{CONFIG_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<WithdrawLamports>, amount: u64) -> Result<()> {
+    //Either sweep everything or leave the vault rent exempt, the system program refuses to leave
+    //a funded account in between
+    let remaining = ctx
+        .accounts
+        .vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(SwapError::InsufficientInput)?;
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+        SwapError::VaultBelowRentExemption
+    );
+
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+    //The vault is a plain system account, so the system program moves its lamports
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault.to_account_info(),
+        to: ctx.accounts.destination.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    transfer(
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+        amount,
+    )?;
+
+    msg!(
+        "Withdrew {} lamports from the vault to {}",
+        amount,
+        ctx.accounts.destination.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawLamports<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      has_one=authority @ SwapError::Unauthorized,
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+      mut,
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: any account can receive lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
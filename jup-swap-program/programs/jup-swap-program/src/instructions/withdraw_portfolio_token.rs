@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::Portfolio;
+use crate::utils::transfer_signed;
+use crate::PORTFOLIO_SEED;
+
+pub fn handler(ctx: Context<WithdrawPortfolioToken>, amount: u64) -> Result<()> {
+    let portfolio = &ctx.accounts.portfolio;
+    let authority_key = portfolio.authority;
+    let id_bytes = portfolio.id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        PORTFOLIO_SEED,
+        authority_key.as_ref(),
+        &id_bytes,
+        &[portfolio.bump],
+    ]];
+
+    transfer_signed(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.portfolio_token_account.to_account_info(),
+        ctx.accounts.destination_token_account.to_account_info(),
+        portfolio.to_account_info(),
+        amount,
+        signer_seeds,
+    )?;
+
+    msg!(
+        "Withdrew {} of {} from portfolio {} to {}",
+        amount,
+        ctx.accounts.mint.key(),
+        portfolio.id,
+        ctx.accounts.destination_token_account.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPortfolioToken<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      has_one=authority @ SwapError::Unauthorized,
+      seeds=[PORTFOLIO_SEED, authority.key().as_ref(), &portfolio.id.to_le_bytes()],
+      bump=portfolio.bump
+    )]
+    pub portfolio: Account<'info, Portfolio>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      mut,
+      associated_token::mint=mint,
+      associated_token::authority=portfolio,
+      associated_token::token_program=token_program,
+    )]
+    pub portfolio_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+      mut,
+      token::mint=mint,
+      token::token_program=token_program,
+    )]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::SwapError;
+use crate::state::Config;
+use crate::utils::transfer_signed;
+use crate::{CONFIG_SEED, VAULT_SEED};
+
+pub fn handler(ctx: Context<WithdrawToken>, amount: u64) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+    transfer_signed(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.vault_token_account.to_account_info(),
+        ctx.accounts.destination_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        amount,
+        signer_seeds,
+    )?;
+
+    msg!(
+        "Withdrew {} of {} from the vault to {}",
+        amount,
+        ctx.accounts.mint.key(),
+        ctx.accounts.destination_token_account.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawToken<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+      has_one=authority @ SwapError::Unauthorized,
+      seeds=[CONFIG_SEED],
+      bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+      seeds=[VAULT_SEED],
+      bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+      mut,
+      associated_token::mint=mint,
+      associated_token::authority=vault,
+      associated_token::token_program=token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+      mut,
+      token::mint=mint,
+      token::token_program=token_program,
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+}
@@ -1,8 +1,18 @@
-use anchor_lang::{prelude::*,solana_program::{instruction::Instruction, program::invoke_signed}};
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, Transfer, transfer, TransferChecked, transfer_checked};
-use jupiter_aggregator::program::Jupiter;
+use anchor_lang::prelude::*;
 use std::str::FromStr;
-use anchor_spl::associated_token::AssociatedToken;
+
+use crate::state::{ConfigChange, PortfolioAsset, Trigger, TwapLimits};
+
+pub mod error;
+pub mod instructions;
+pub mod mint_risks;
+pub mod oracle;
+pub mod state;
+#[cfg(feature = "cpi")]
+pub mod swap_cpi;
+pub mod utils;
+
+pub use instructions::*;
 
 declare_program!(jupiter_aggregator);
 declare_id!("LMMGrBSX84ZC519PSBkppyVdT4XfM3VP3hw4XLXqhrf");
@@ -12,124 +22,301 @@ declare_id!("LMMGrBSX84ZC519PSBkppyVdT4XfM3VP3hw4XLXqhrf");
 //  - sends the purchased tokens to receiver
 
 const VAULT_SEED: &[u8] = b"vault";
+const CONFIG_SEED: &[u8] = b"config";
+const NONCE_SEED: &[u8] = b"nonce";
+const INTENT_AUTHORITY_SEED: &[u8] = b"intent_authority";
+const DELEGATION_SEED: &[u8] = b"delegation";
+const ORDER_SEED: &[u8] = b"order";
+const DCA_SEED: &[u8] = b"dca";
+const TWAP_SEED: &[u8] = b"twap";
+const INVOICE_SEED: &[u8] = b"invoice";
+const RECEIPT_SEED: &[u8] = b"receipt";
+const VESTING_SEED: &[u8] = b"vesting";
+const VESTING_ESCROW_SEED: &[u8] = b"vesting_escrow";
+const MINT_RATE_LIMIT_SEED: &[u8] = b"mint_rate_limit";
+const CALLER_RATE_LIMIT_SEED: &[u8] = b"caller_rate_limit";
+const INTEGRATOR_SEED: &[u8] = b"integrator";
+const REFERRER_SEED: &[u8] = b"referrer";
+const TRIGGER_ORDER_SEED: &[u8] = b"trigger_order";
+const PORTFOLIO_SEED: &[u8] = b"portfolio";
+const PENDING_CHANGE_SEED: &[u8] = b"pending_change";
+const MINT_STATS_SEED: &[u8] = b"mint_stats";
+
+const BPS_DENOMINATOR: u64 = 10_000;
 
 pub fn jupiter_program_id() -> Pubkey {
     Pubkey::from_str("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").unwrap()
 }
 
+pub fn memo_program_id() -> Pubkey {
+    Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr").unwrap()
+}
+
 #[program]
 pub mod jup_swap_program {
     use super::*;
 
-      pub fn swap(ctx: Context<Swap>, data: Vec<u8>, amount: u64) -> Result<()> {
-        //validate jupiter program id
-        require_keys_eq!(*ctx.accounts.jupiter_program.key, jupiter_program_id());
-
-        //Convert the remaining accounts gotten from the jupiter swap api to Account Meta Objects
-        let accounts: Vec<AccountMeta> = ctx
-            .remaining_accounts
-            .iter()
-            .map(|acc| {
-                let is_signer = acc.key == &ctx.accounts.vault.key();
-                AccountMeta {
-                    pubkey: *acc.key,
-                    is_signer,
-                    is_writable: acc.is_writable,
-                }
-            })
-            .collect();
-        
-        //Convert the remaining accounts to account infos
-        let accounts_infos: Vec<AccountInfo> = ctx
-            .remaining_accounts
-            .iter()
-            .map(|acc| AccountInfo { ..acc.clone() })
-            .collect();
-
-        //PDA signer seeds for the vault 
-        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
-   
-        //invoke the cpi call to jupiter program 
-        invoke_signed(
-            &Instruction {
-                program_id: ctx.accounts.jupiter_program.key(),
-                accounts,
-                data,
-            },
-            &accounts_infos,
-            signer_seeds,
-        )?;
-            msg!("Transferring tokens...");
-            msg!(
-                "Mint: {}",
-                &ctx.accounts.output_mint.to_account_info().key()
-            );
-            msg!(
-                "From Vault Output Token Account: {}",
-                &ctx.accounts.vault_output_token_account.key()
-            );
-            msg!(
-                "To Recipient Token Address: {}",
-                &ctx.accounts.recipient_token_account.key()
-            );
-         msg!("Vault PDA: {}", ctx.accounts.vault.key());
-         msg!("Vault Output Token Account Authority: {:?}", ctx.accounts.vault_output_token_account.owner);
-
-        //Transfer swapped tokens to recipient 
-        let decimals = ctx.accounts.output_mint.decimals;
-        let cpi_accounts = TransferChecked { 
-                mint:ctx.accounts.output_mint.to_account_info(),
-                from: ctx.accounts.vault_output_token_account.to_account_info(),
-                to: ctx.accounts.recipient_token_account.to_account_info(),
-                authority: ctx.accounts.vault.to_account_info(),
-        };
-        let cpi_program =  ctx.accounts.output_mint_token_program.to_account_info();
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-        transfer_checked(cpi_context, amount, decimals)?;
-
-        msg!("Tokens transferred successfully.");
-
-        Ok(())
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+        data: Vec<u8>,
+        amount: u64,
+        callback: Option<SwapCallback>,
+        receipt_id: Option<u64>,
+        vesting_schedule: Option<VestingSchedule>,
+        memo: Option<String>,
+    ) -> Result<SwapResult> {
+        instructions::swap::handler(
+            ctx,
+            data,
+            amount,
+            callback,
+            receipt_id,
+            vesting_schedule,
+            memo,
+        )
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        instructions::claim_vested::handler(ctx)
+    }
+
+    pub fn close_receipt(ctx: Context<CloseReceipt>) -> Result<()> {
+        instructions::close_receipt::handler(ctx)
+    }
+
+    pub fn execute_intent(
+        ctx: Context<ExecuteIntent>,
+        intent: SwapIntent,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::execute_intent::handler(ctx, intent, data)
+    }
+
+    pub fn create_delegation(
+        ctx: Context<CreateDelegation>,
+        keeper: Pubkey,
+        cap: u64,
+        rate_in: u64,
+        min_out_per_rate_in: u64,
+    ) -> Result<()> {
+        instructions::create_delegation::handler(ctx, keeper, cap, rate_in, min_out_per_rate_in)
+    }
+
+    pub fn close_delegation(ctx: Context<CloseDelegation>) -> Result<()> {
+        instructions::close_delegation::handler(ctx)
+    }
+
+    pub fn swap_from_delegate(
+        ctx: Context<SwapFromDelegate>,
+        data: Vec<u8>,
+        amount_in: u64,
+        min_out: u64,
+    ) -> Result<()> {
+        instructions::swap_from_delegate::handler(ctx, data, amount_in, min_out)
+    }
+
+    pub fn create_order(
+        ctx: Context<CreateOrder>,
+        id: u64,
+        amount_in: u64,
+        min_amount_out: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        instructions::create_order::handler(ctx, id, amount_in, min_amount_out, expiry)
+    }
+
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        instructions::cancel_order::handler(ctx)
+    }
+
+    pub fn fill_order(ctx: Context<FillOrder>, data: Vec<u8>) -> Result<()> {
+        instructions::fill_order::handler(ctx, data)
+    }
+
+    pub fn create_trigger_order(
+        ctx: Context<CreateTriggerOrder>,
+        id: u64,
+        amount_in: u64,
+        min_amount_out: u64,
+        trigger: Trigger,
+    ) -> Result<()> {
+        instructions::create_trigger_order::handler(ctx, id, amount_in, min_amount_out, trigger)
+    }
+
+    pub fn cancel_trigger_order(ctx: Context<CancelTriggerOrder>) -> Result<()> {
+        instructions::cancel_trigger_order::handler(ctx)
+    }
+
+    pub fn trigger_order(ctx: Context<FillTriggerOrder>, data: Vec<u8>) -> Result<()> {
+        instructions::trigger_order::handler(ctx, data)
+    }
+
+    pub fn create_dca(
+        ctx: Context<CreateDca>,
+        id: u64,
+        total_input: u64,
+        amount_per_cycle: u64,
+        interval: i64,
+        min_out_per_cycle: u64,
+    ) -> Result<()> {
+        instructions::create_dca::handler(
+            ctx,
+            id,
+            total_input,
+            amount_per_cycle,
+            interval,
+            min_out_per_cycle,
+        )
+    }
+
+    pub fn execute_dca_cycle(ctx: Context<ExecuteDcaCycle>, data: Vec<u8>) -> Result<()> {
+        instructions::execute_dca_cycle::handler(ctx, data)
+    }
+
+    pub fn withdraw_dca(ctx: Context<WithdrawDca>, amount: u64) -> Result<()> {
+        instructions::withdraw_dca::handler(ctx, amount)
+    }
+
+    pub fn close_dca(ctx: Context<CloseDca>) -> Result<()> {
+        instructions::close_dca::handler(ctx)
+    }
+
+    pub fn create_twap(
+        ctx: Context<CreateTwap>,
+        id: u64,
+        total_input: u64,
+        num_slices: u32,
+        min_slot_gap: u64,
+        limits: TwapLimits,
+    ) -> Result<()> {
+        instructions::create_twap::handler(ctx, id, total_input, num_slices, min_slot_gap, limits)
     }
-}
 
-#[derive(Accounts)]
-pub struct Swap<'info> {
-    pub input_mint: InterfaceAccount<'info, Mint>,
-    pub input_mint_token_program: Interface<'info, TokenInterface>,
-    pub output_mint: InterfaceAccount<'info, Mint>,
-    pub output_mint_token_program: Interface<'info, TokenInterface>,
-
-    #[account(
-      mut,
-      seeds=[VAULT_SEED],
-      bump
-    )]
-    pub vault: SystemAccount<'info>,
-
-    #[account(
-      mut,
-      associated_token::mint=input_mint,
-      associated_token::authority=vault,
-      associated_token::token_program=input_mint_token_program,
-    )]
-    pub vault_input_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    #[account(
-      mut,
-      associated_token::mint=output_mint,
-      associated_token::authority=vault,
-      associated_token::token_program=output_mint_token_program,
-    )]
-    pub vault_output_token_account: InterfaceAccount<'info, TokenAccount>,
-    #[account(
-        mut,
-        associated_token::mint=output_mint,
-        associated_token::authority=recipient,
-        associated_token::token_program=output_mint_token_program,
-    )]
-    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
-    pub recipient: SystemAccount<'info>, 
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub jupiter_program: Program<'info, Jupiter>,
+    pub fn fill_twap_slice(ctx: Context<FillTwapSlice>, data: Vec<u8>) -> Result<()> {
+        instructions::fill_twap_slice::handler(ctx, data)
+    }
+
+    pub fn cancel_twap(ctx: Context<CancelTwap>) -> Result<()> {
+        instructions::cancel_twap::handler(ctx)
+    }
+
+    pub fn create_invoice(
+        ctx: Context<CreateInvoice>,
+        id: u64,
+        amount: u64,
+        expiry: i64,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::create_invoice::handler(ctx, id, amount, expiry, memo_hash)
+    }
+
+    pub fn pay_invoice(ctx: Context<PayInvoice>, data: Vec<u8>, amount_in: u64) -> Result<()> {
+        instructions::pay_invoice::handler(ctx, data, amount_in)
+    }
+
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        instructions::initialize_config::handler(ctx)
+    }
+
+    pub fn queue_config_change(
+        ctx: Context<QueueConfigChange>,
+        id: u64,
+        change: ConfigChange,
+    ) -> Result<()> {
+        instructions::queue_config_change::handler(ctx, id, change)
+    }
+
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::execute_config_change::handler(ctx)
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        instructions::cancel_config_change::handler(ctx)
+    }
+
+    pub fn set_mint_rate_limit(
+        ctx: Context<SetMintRateLimit>,
+        vault: Pubkey,
+        cap: Option<u64>,
+    ) -> Result<()> {
+        instructions::set_mint_rate_limit::handler(ctx, vault, cap)
+    }
+
+    pub fn set_caller_rate_limit(
+        ctx: Context<SetCallerRateLimit>,
+        vault: Pubkey,
+        caller: Pubkey,
+        cap: Option<u64>,
+    ) -> Result<()> {
+        instructions::set_caller_rate_limit::handler(ctx, vault, caller, cap)
+    }
+
+    pub fn create_integrator(
+        ctx: Context<CreateIntegrator>,
+        id: u64,
+        authority: Pubkey,
+        fee_bps: u16,
+        fee_treasury: Pubkey,
+    ) -> Result<()> {
+        instructions::create_integrator::handler(ctx, id, authority, fee_bps, fee_treasury)
+    }
+
+    pub fn approve_referrer(ctx: Context<ApproveReferrer>) -> Result<()> {
+        instructions::approve_referrer::handler(ctx)
+    }
+
+    pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+        instructions::claim_referral::handler(ctx)
+    }
+
+    pub fn withdraw_integrator_token(
+        ctx: Context<WithdrawIntegratorToken>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_integrator_token::handler(ctx, amount)
+    }
+
+    pub fn create_portfolio(
+        ctx: Context<CreatePortfolio>,
+        id: u64,
+        assets: Vec<PortfolioAsset>,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        instructions::create_portfolio::handler(ctx, id, assets, max_slippage_bps)
+    }
+
+    pub fn set_portfolio_targets(
+        ctx: Context<SetPortfolioTargets>,
+        assets: Vec<PortfolioAsset>,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        instructions::set_portfolio_targets::handler(ctx, assets, max_slippage_bps)
+    }
+
+    pub fn rebalance_portfolio<'info>(
+        ctx: Context<'_, '_, '_, 'info, RebalancePortfolio<'info>>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::rebalance_portfolio::handler(ctx, data)
+    }
+
+    pub fn withdraw_portfolio_token(
+        ctx: Context<WithdrawPortfolioToken>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_portfolio_token::handler(ctx, amount)
+    }
+
+    pub fn withdraw_token(ctx: Context<WithdrawToken>, amount: u64) -> Result<()> {
+        instructions::withdraw_token::handler(ctx, amount)
+    }
+
+    pub fn withdraw_lamports(ctx: Context<WithdrawLamports>, amount: u64) -> Result<()> {
+        instructions::withdraw_lamports::handler(ctx, amount)
+    }
+
+    pub fn close_vault_token_account(ctx: Context<CloseVaultTokenAccount>) -> Result<()> {
+        instructions::close_vault_token_account::handler(ctx)
+    }
 }
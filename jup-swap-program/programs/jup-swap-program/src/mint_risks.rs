@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state::DefaultAccountState, permanent_delegate::PermanentDelegate,
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    state::AccountState,
+};
+
+// Ways a mint can trap or claw back what's paid out in it, as bit flags
+/// Someone can transfer or burn out of any of the mint's token accounts
+pub const PERMANENT_DELEGATE: u8 = 1 << 0;
+/// Tokens can never leave the account they're paid into
+pub const NON_TRANSFERABLE: u8 = 1 << 1;
+/// New token accounts start out frozen
+pub const DEFAULT_FROZEN: u8 = 1 << 2;
+/// Someone can freeze any of the mint's token accounts
+pub const FREEZE_AUTHORITY: u8 = 1 << 3;
+
+/// Blocked by default. Freeze authorities are common on regular stablecoins, so they're opt-in.
+pub const DEFAULT_BLOCKED_MINT_RISKS: u8 = PERMANENT_DELEGATE | NON_TRANSFERABLE | DEFAULT_FROZEN;
+
+/// The risk flags of a Token or Token-2022 mint account.
+pub fn mint_risks(mint: &AccountInfo) -> Result<u8> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    let mut risks = 0;
+    if state.base.freeze_authority.is_some() {
+        risks |= FREEZE_AUTHORITY;
+    }
+    //Regular Token mints have no extensions to look at
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(risks);
+    }
+    if let Ok(permanent_delegate) = state.get_extension::<PermanentDelegate>() {
+        if Option::<Pubkey>::from(permanent_delegate.delegate).is_some() {
+            risks |= PERMANENT_DELEGATE;
+        }
+    }
+    if state
+        .get_extension_types()?
+        .contains(&ExtensionType::NonTransferable)
+    {
+        risks |= NON_TRANSFERABLE;
+    }
+    if let Ok(default_state) = state.get_extension::<DefaultAccountState>() {
+        if default_state.state == AccountState::Frozen as u8 {
+            risks |= DEFAULT_FROZEN;
+        }
+    }
+    Ok(risks)
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::error::SwapError;
+use crate::BPS_DENOMINATOR;
+
+// Layout of the Pyth receiver's `PriceUpdateV2` account:
+// [discriminator: 8, write_authority: 32, verification_level: 1 or 2, PriceFeedMessage, posted_slot: 8]
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+const VERIFICATION_LEVEL_OFFSET: usize = 8 + 32;
+// Borsh enum tag of `VerificationLevel::Full`, `Partial` (0) is followed by its signature count
+const VERIFICATION_LEVEL_FULL: u8 = 1;
+// feed_id: 32, price: 8, conf: 8, exponent: 4, publish_time: 8
+const PRICE_MESSAGE_LEN: usize = 32 + 8 + 8 + 4 + 8;
+
+pub fn pyth_receiver_program_id() -> Pubkey {
+    Pubkey::from_str("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ").unwrap()
+}
+
+/// The parts of a Pyth price update we check swaps against, the price is `price * 10^exponent`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Parses a fully verified `PriceUpdateV2` account owned by the Pyth receiver program.
+    pub fn load(account: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(
+            *account.owner,
+            pyth_receiver_program_id(),
+            SwapError::InvalidOracleAccount
+        );
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() > VERIFICATION_LEVEL_OFFSET && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+            SwapError::InvalidOracleAccount
+        );
+        //Partially verified updates haven't been checked against enough guardian signatures
+        require!(
+            data[VERIFICATION_LEVEL_OFFSET] == VERIFICATION_LEVEL_FULL,
+            SwapError::InvalidOracleAccount
+        );
+        let message = data
+            .get(VERIFICATION_LEVEL_OFFSET + 1..VERIFICATION_LEVEL_OFFSET + 1 + PRICE_MESSAGE_LEN)
+            .ok_or(SwapError::InvalidOracleAccount)?;
+
+        let read = |offset: usize, len: usize| &message[offset..offset + len];
+        let mut feed_id = [0u8; 32];
+        feed_id.copy_from_slice(read(0, 32));
+        let price = i64::from_le_bytes(read(32, 8).try_into().unwrap());
+        let exponent = i32::from_le_bytes(read(48, 4).try_into().unwrap());
+        let publish_time = i64::from_le_bytes(read(52, 8).try_into().unwrap());
+        require_gt!(price, 0, SwapError::InvalidOracleAccount);
+
+        Ok(Self {
+            feed_id,
+            price,
+            exponent,
+            publish_time,
+        })
+    }
+
+    pub fn require_fresh(&self, now: i64, max_age: i64) -> Result<()> {
+        let age = now
+            .checked_sub(self.publish_time)
+            .ok_or(SwapError::MathOverflow)?;
+        require_gte!(max_age, age, SwapError::StaleOraclePrice);
+        Ok(())
+    }
+
+    /// Compares this price with `price * 10^exponent`.
+    pub fn cmp_price(&self, price: i64, exponent: i32) -> Result<Ordering> {
+        let scale = |value: i64, by: i32| -> Result<i128> {
+            let factor = u32::try_from(by)
+                .ok()
+                .and_then(|by| 10i128.checked_pow(by))
+                .ok_or(SwapError::MathOverflow)?;
+            i128::from(value)
+                .checked_mul(factor)
+                .ok_or(SwapError::MathOverflow.into())
+        };
+        //Bring both to the smaller exponent
+        let (ours, theirs) = if self.exponent > exponent {
+            (
+                scale(self.price, self.exponent - exponent)?,
+                i128::from(price),
+            )
+        } else {
+            (
+                i128::from(self.price),
+                scale(price, exponent - self.exponent)?,
+            )
+        };
+        Ok(ours.cmp(&theirs))
+    }
+}
+
+/// Rejects a swap of `amount_in` for `amount_out` (raw token amounts) whose value at the oracle
+/// prices differs by more than `max_deviation_bps` of the input value, either way.
+pub fn check_oracle_rate(
+    amount_in: u64,
+    input_decimals: u8,
+    input_price: &OraclePrice,
+    amount_out: u64,
+    output_decimals: u8,
+    output_price: &OraclePrice,
+    max_deviation_bps: u16,
+) -> Result<()> {
+    //Both values as an integer times 10^exponent, then brought to the smaller exponent
+    let value_in = u128::from(amount_in)
+        .checked_mul(input_price.price as u128)
+        .ok_or(SwapError::MathOverflow)?;
+    let exponent_in = i64::from(input_price.exponent) - i64::from(input_decimals);
+    let value_out = u128::from(amount_out)
+        .checked_mul(output_price.price as u128)
+        .ok_or(SwapError::MathOverflow)?;
+    let exponent_out = i64::from(output_price.exponent) - i64::from(output_decimals);
+
+    let scale = |value: u128, by: i64| -> Result<u128> {
+        let factor = u32::try_from(by)
+            .ok()
+            .and_then(|by| 10u128.checked_pow(by))
+            .ok_or(SwapError::MathOverflow)?;
+        value
+            .checked_mul(factor)
+            .ok_or(SwapError::MathOverflow.into())
+    };
+    let (value_in, value_out) = if exponent_in > exponent_out {
+        (scale(value_in, exponent_in - exponent_out)?, value_out)
+    } else {
+        (value_in, scale(value_out, exponent_out - exponent_in)?)
+    };
+
+    let deviation = value_in.abs_diff(value_out);
+    require!(
+        deviation
+            .checked_mul(u128::from(BPS_DENOMINATOR))
+            .ok_or(SwapError::MathOverflow)?
+            <= value_in
+                .checked_mul(u128::from(max_deviation_bps))
+                .ok_or(SwapError::MathOverflow)?,
+        SwapError::OracleDeviationExceeded
+    );
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::jupiter_program_id;
+use crate::mint_risks::DEFAULT_BLOCKED_MINT_RISKS;
+
+pub const MAX_CALLBACK_PROGRAMS: usize = 8;
+pub const MAX_ORACLE_FEEDS: usize = 16;
+pub const DEFAULT_ORACLE_MAX_DEVIATION_BPS: u16 = 100;
+pub const DEFAULT_ORACLE_MAX_AGE: i64 = 60;
+pub const DEFAULT_TIMELOCK_DELAY: i64 = 2 * 24 * 60 * 60;
+pub const MAX_AGGREGATORS: usize = 4;
+pub const MAX_AGGREGATOR_DISCRIMINATORS: usize = 8;
+pub const MAX_TRUSTED_MINTS: usize = 16;
+
+// Jupiter's route, route_with_token_ledger, exact_out_route, shared_accounts_route,
+// shared_accounts_route_with_token_ledger and shared_accounts_exact_out_route
+const JUPITER_ROUTE_DISCRIMINATORS: [[u8; 8]; 6] = [
+    [229, 23, 203, 151, 122, 227, 173, 42],
+    [150, 86, 71, 116, 167, 93, 14, 104],
+    [208, 51, 239, 151, 123, 43, 237, 92],
+    [193, 32, 155, 51, 65, 214, 156, 129],
+    [230, 121, 143, 80, 119, 159, 106, 170],
+    [176, 209, 105, 168, 154, 125, 69, 62],
+];
+
+/// Pyth price feed to check a mint's swaps against.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct OracleFeed {
+    pub mint: Pubkey,
+    pub feed_id: [u8; 32],
+}
+
+/// Program swaps may be routed through, an aggregator or a single AMM, and the instructions of
+/// it they may call, by their first 8 bytes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct Aggregator {
+    pub program_id: Pubkey,
+    #[max_len(MAX_AGGREGATOR_DISCRIMINATORS)]
+    pub discriminators: Vec<[u8; 8]>,
+}
+
+impl Aggregator {
+    /// The default entry, Jupiter v6 and its route instructions.
+    pub fn jupiter() -> Self {
+        Self {
+            program_id: jupiter_program_id(),
+            discriminators: JUPITER_ROUTE_DISCRIMINATORS.to_vec(),
+        }
+    }
+}
+
+/// Program wide settings, a single PDA created once by the program's upgrade authority.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    /// Can sweep the vault and queue changes to these settings
+    pub authority: Pubkey,
+    /// Programs `swap` may call back into after paying out
+    #[max_len(MAX_CALLBACK_PROGRAMS)]
+    pub callback_programs: Vec<Pubkey>,
+    /// How far a swap's rate may be from the oracle rate when oracles are passed
+    pub oracle_max_deviation_bps: u16,
+    /// Oldest oracle price `swap` accepts, in seconds
+    pub oracle_max_age: i64,
+    #[max_len(MAX_ORACLE_FEEDS)]
+    pub oracle_feeds: Vec<OracleFeed>,
+    /// Length of the rate limit windows in slots, 0 turns rate limiting off
+    pub rate_limit_window_slots: u64,
+    /// Output a mint can be paid out per window unless its rate limit sets its own cap
+    pub default_mint_cap: u64,
+    /// Output of a mint a caller can be paid per window unless its rate limit sets its own cap
+    pub default_caller_cap: u64,
+    /// Can cancel queued changes before they apply
+    pub guardian: Pubkey,
+    /// Seconds between queueing a change and it becoming executable
+    pub timelock_delay: i64,
+    /// Programs swaps are routed through
+    #[max_len(MAX_AGGREGATORS)]
+    pub aggregators: Vec<Aggregator>,
+    /// `mint_risks` flags `swap` refuses to pay out in
+    pub blocked_mint_risks: u8,
+    /// Mints paid out in regardless of their risk flags
+    #[max_len(MAX_TRUSTED_MINTS)]
+    pub trusted_mints: Vec<Pubkey>,
+    /// Owner of the accounts swap surpluses go to, the payer when not set
+    pub surplus_treasury: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl Config {
+    /// A fresh config with every setting at its default.
+    pub fn new(authority: Pubkey, bump: u8) -> Self {
+        Self {
+            authority,
+            callback_programs: vec![],
+            oracle_max_deviation_bps: DEFAULT_ORACLE_MAX_DEVIATION_BPS,
+            oracle_max_age: DEFAULT_ORACLE_MAX_AGE,
+            oracle_feeds: vec![],
+            rate_limit_window_slots: 0,
+            default_mint_cap: u64::MAX,
+            default_caller_cap: u64::MAX,
+            guardian: authority,
+            timelock_delay: DEFAULT_TIMELOCK_DELAY,
+            aggregators: vec![Aggregator::jupiter()],
+            blocked_mint_risks: DEFAULT_BLOCKED_MINT_RISKS,
+            trusted_mints: vec![],
+            surplus_treasury: None,
+            bump,
+        }
+    }
+
+    pub fn oracle_feed_id(&self, mint: &Pubkey) -> Option<[u8; 32]> {
+        self.oracle_feeds
+            .iter()
+            .find(|feed| feed.mint == *mint)
+            .map(|feed| feed.feed_id)
+    }
+
+    /// Fails unless `program_id` is an allowlisted aggregator and `data` calls one of the
+    /// instructions allowed for it.
+    pub fn check_route(&self, program_id: &Pubkey, data: &[u8]) -> Result<()> {
+        let aggregator = self
+            .aggregators
+            .iter()
+            .find(|aggregator| aggregator.program_id == *program_id)
+            .ok_or(SwapError::AggregatorNotAllowed)?;
+        require!(
+            data.len() >= 8
+                && aggregator
+                    .discriminators
+                    .iter()
+                    .any(|discriminator| data[..8] == discriminator[..]),
+            SwapError::RouteInstructionNotAllowed
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+/// Recurring order swapping `amount_per_cycle` of the escrowed input every `interval` seconds
+/// and crediting `recipient`. Cycles are permissionless, anyone can execute one once
+/// `next_cycle_at` has passed.
+#[account]
+#[derive(InitSpace)]
+pub struct DcaOrder {
+    pub owner: Pubkey,
+    pub id: u64,
+    pub recipient: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub total_input: u64,
+    pub amount_per_cycle: u64,
+    /// Seconds between cycles
+    pub interval: i64,
+    /// Minimum output for a full cycle, pro-rated when the last cycle is smaller
+    pub min_out_per_cycle: u64,
+    pub next_cycle_at: i64,
+    pub input_spent: u64,
+    pub output_received: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+/// Lets `keeper` swap `owner`'s `input_mint` tokens into `output_mint` through the vault. The
+/// owner approves this account as delegate on `input_token_account`, and the spending cap is
+/// the delegated amount of that approval, enforced by the token program. A token account has a
+/// single delegate, so there is one delegation per input token account. The owner also picks
+/// the worst rate keepers may swap at, so a keeper can't spend the allowance on a bad route.
+#[account]
+#[derive(InitSpace)]
+pub struct Delegation {
+    pub owner: Pubkey,
+    pub keeper: Pubkey,
+    pub input_mint: Pubkey,
+    pub input_token_account: Pubkey,
+    pub output_mint: Pubkey,
+    /// Input amount the minimum rate is quoted for
+    pub rate_in: u64,
+    /// Minimum output for `rate_in` of input, pro-rated to each swap's input
+    pub min_out_per_rate_in: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_INTEGRATOR_MINTS: usize = 16;
+pub const MAX_INTEGRATOR_RELAYERS: usize = 8;
+
+/// A partner white-labelling `swap`. Its swaps run out of its own vault, the PDA of
+/// `[VAULT_SEED, integrator]`, with their own rate limits, and pay it `fee_bps` of the payout,
+/// `referral_share_bps` of that fee going to the swap's referrer when it has one.
+#[account]
+#[derive(InitSpace)]
+pub struct Integrator {
+    pub id: u64,
+    /// Partner key managing these settings and the integrator's vault
+    pub authority: Pubkey,
+    pub fee_bps: u16,
+    /// Owner of the token accounts fees are paid into
+    pub fee_treasury: Pubkey,
+    /// Share of the fee paid to the swap's referrer, when it has one
+    pub referral_share_bps: u16,
+    /// Mints the integrator's swaps may use, any mint when empty
+    #[max_len(MAX_INTEGRATOR_MINTS)]
+    pub allowed_mints: Vec<Pubkey>,
+    /// Keys that may sign the integrator's swaps, anyone when empty
+    #[max_len(MAX_INTEGRATOR_RELAYERS)]
+    pub relayers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Integrator {
+    pub fn allows_mint(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.is_empty() || self.allowed_mints.contains(mint)
+    }
+
+    pub fn allows_relayer(&self, relayer: &Pubkey) -> bool {
+        self.relayers.is_empty() || self.relayers.contains(relayer)
+    }
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+/// Request from `merchant` to be paid exactly `amount` of `output_mint` before `expiry`. Anyone
+/// can pay it with whatever token they hold, the route's output above `amount` goes back to them.
+#[account]
+#[derive(InitSpace)]
+pub struct Invoice {
+    pub merchant: Pubkey,
+    pub id: u64,
+    pub output_mint: Pubkey,
+    pub amount: u64,
+    pub expiry: i64,
+    /// Hash of the off-chain memo (order number, line items...) the invoice is for
+    pub memo_hash: [u8; 32],
+    pub paid: bool,
+    pub bump: u8,
+}
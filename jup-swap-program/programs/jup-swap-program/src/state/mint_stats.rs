@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+
+/// Running totals of every `swap` from `input_mint` to `output_mint`, across all vaults.
+#[account]
+#[derive(InitSpace)]
+pub struct MintStats {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// Input spent by the routes
+    pub volume_in: u128,
+    /// Output delivered by the routes, payouts, fees and surpluses included
+    pub volume_out: u128,
+    pub swap_count: u64,
+    /// Integrator fees, referral shares included
+    pub total_fees: u128,
+    pub last_slot: u64,
+    pub bump: u8,
+}
+
+impl MintStats {
+    pub fn record(&mut self, amount_in: u64, amount_out: u64, fee: u64, slot: u64) -> Result<()> {
+        self.volume_in = self
+            .volume_in
+            .checked_add(u128::from(amount_in))
+            .ok_or(SwapError::MathOverflow)?;
+        self.volume_out = self
+            .volume_out
+            .checked_add(u128::from(amount_out))
+            .ok_or(SwapError::MathOverflow)?;
+        self.total_fees = self
+            .total_fees
+            .checked_add(u128::from(fee))
+            .ok_or(SwapError::MathOverflow)?;
+        self.swap_count = self
+            .swap_count
+            .checked_add(1)
+            .ok_or(SwapError::MathOverflow)?;
+        self.last_slot = slot;
+        Ok(())
+    }
+}
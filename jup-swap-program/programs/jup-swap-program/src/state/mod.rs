@@ -0,0 +1,33 @@
+pub mod config;
+pub mod dca_order;
+pub mod delegation;
+pub mod integrator;
+pub mod invoice;
+pub mod mint_stats;
+pub mod order;
+pub mod pending_change;
+pub mod portfolio;
+pub mod rate_limit;
+pub mod receipt;
+pub mod referrer;
+pub mod trigger_order;
+pub mod twap_order;
+pub mod user_nonce;
+pub mod vesting;
+
+pub use config::*;
+pub use dca_order::*;
+pub use delegation::*;
+pub use integrator::*;
+pub use invoice::*;
+pub use mint_stats::*;
+pub use order::*;
+pub use pending_change::*;
+pub use portfolio::*;
+pub use rate_limit::*;
+pub use receipt::*;
+pub use referrer::*;
+pub use trigger_order::*;
+pub use twap_order::*;
+pub use user_nonce::*;
+pub use vesting::*;
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+/// Limit order escrowing `amount_in` of `input_mint` in the order's own token account. Any
+/// keeper can fill it before `expiry` as long as the route pays out at least `min_amount_out`,
+/// i.e. the limit price is `min_amount_out / amount_in`.
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    pub owner: Pubkey,
+    pub id: u64,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub expiry: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::state::{
+    Aggregator, OracleFeed, MAX_AGGREGATORS, MAX_AGGREGATOR_DISCRIMINATORS, MAX_CALLBACK_PROGRAMS,
+    MAX_INTEGRATOR_MINTS, MAX_INTEGRATOR_RELAYERS, MAX_ORACLE_FEEDS, MAX_TRUSTED_MINTS,
+};
+use crate::BPS_DENOMINATOR;
+
+/// A settings change that only applies once its timelock has passed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum ConfigChange {
+    Authority {
+        authority: Pubkey,
+    },
+    Guardian {
+        guardian: Pubkey,
+    },
+    TimelockDelay {
+        delay: i64,
+    },
+    Aggregators {
+        #[max_len(MAX_AGGREGATORS)]
+        aggregators: Vec<Aggregator>,
+    },
+    CallbackPrograms {
+        #[max_len(MAX_CALLBACK_PROGRAMS)]
+        programs: Vec<Pubkey>,
+    },
+    OracleConfig {
+        max_deviation_bps: u16,
+        max_age: i64,
+        #[max_len(MAX_ORACLE_FEEDS)]
+        feeds: Vec<OracleFeed>,
+    },
+    MintRisks {
+        blocked_mint_risks: u8,
+        #[max_len(MAX_TRUSTED_MINTS)]
+        trusted_mints: Vec<Pubkey>,
+    },
+    SurplusTreasury {
+        surplus_treasury: Option<Pubkey>,
+    },
+    RateLimits {
+        window_slots: u64,
+        default_mint_cap: u64,
+        default_caller_cap: u64,
+    },
+    /// Applied by `set_mint_rate_limit`
+    MintRateLimit {
+        vault: Pubkey,
+        mint: Pubkey,
+        cap: Option<u64>,
+    },
+    /// Applied by `set_caller_rate_limit`
+    CallerRateLimit {
+        vault: Pubkey,
+        caller: Pubkey,
+        mint: Pubkey,
+        cap: Option<u64>,
+    },
+    /// Applied by `create_integrator`
+    NewIntegrator {
+        id: u64,
+        authority: Pubkey,
+        fee_bps: u16,
+        fee_treasury: Pubkey,
+    },
+    /// Queued by the integrator's authority rather than the config's. Setting a new `authority`
+    /// hands the integrator over, leaving changes the old one queued unexecutable
+    Integrator {
+        id: u64,
+        authority: Pubkey,
+        fee_bps: u16,
+        fee_treasury: Pubkey,
+        referral_share_bps: u16,
+        #[max_len(MAX_INTEGRATOR_MINTS)]
+        allowed_mints: Vec<Pubkey>,
+        #[max_len(MAX_INTEGRATOR_RELAYERS)]
+        relayers: Vec<Pubkey>,
+    },
+}
+
+impl ConfigChange {
+    pub fn validate(&self) -> Result<()> {
+        let bps = |bps: u16| -> Result<()> {
+            require_gte!(BPS_DENOMINATOR, u64::from(bps), SwapError::InvalidBps);
+            Ok(())
+        };
+        match self {
+            Self::TimelockDelay { delay } => {
+                require_gte!(*delay, 0, SwapError::InvalidInterval);
+            }
+            Self::Aggregators { aggregators } => {
+                require_gte!(MAX_AGGREGATORS, aggregators.len(), SwapError::ListTooLong);
+                require!(
+                    aggregators
+                        .iter()
+                        .all(|aggregator| aggregator.discriminators.len()
+                            <= MAX_AGGREGATOR_DISCRIMINATORS),
+                    SwapError::ListTooLong
+                );
+            }
+            Self::CallbackPrograms { programs } => {
+                require_gte!(
+                    MAX_CALLBACK_PROGRAMS,
+                    programs.len(),
+                    SwapError::ListTooLong
+                );
+            }
+            Self::OracleConfig {
+                max_deviation_bps,
+                max_age,
+                feeds,
+            } => {
+                bps(*max_deviation_bps)?;
+                require_gte!(*max_age, 0, SwapError::InvalidInterval);
+                require_gte!(MAX_ORACLE_FEEDS, feeds.len(), SwapError::ListTooLong);
+            }
+            Self::MintRisks { trusted_mints, .. } => {
+                require_gte!(
+                    MAX_TRUSTED_MINTS,
+                    trusted_mints.len(),
+                    SwapError::ListTooLong
+                );
+            }
+            Self::NewIntegrator { fee_bps, .. } => bps(*fee_bps)?,
+            Self::Integrator {
+                fee_bps,
+                referral_share_bps,
+                allowed_mints,
+                relayers,
+                ..
+            } => {
+                bps(*fee_bps)?;
+                bps(*referral_share_bps)?;
+                require_gte!(
+                    MAX_INTEGRATOR_MINTS,
+                    allowed_mints.len(),
+                    SwapError::ListTooLong
+                );
+                require_gte!(
+                    MAX_INTEGRATOR_RELAYERS,
+                    relayers.len(),
+                    SwapError::ListTooLong
+                );
+            }
+            Self::Authority { .. }
+            | Self::Guardian { .. }
+            | Self::RateLimits { .. }
+            | Self::SurplusTreasury { .. }
+            | Self::MintRateLimit { .. }
+            | Self::CallerRateLimit { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// The integrator the change applies to, if it isn't a config change.
+    pub fn integrator_id(&self) -> Option<u64> {
+        match self {
+            Self::Integrator { id, .. } => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+/// A change queued by `proposer`, executable by anyone from `eta` on unless the guardian or the
+/// proposer cancels it first.
+#[account]
+#[derive(InitSpace)]
+pub struct PendingChange {
+    pub proposer: Pubkey,
+    pub id: u64,
+    pub change: ConfigChange,
+    pub eta: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+use crate::BPS_DENOMINATOR;
+
+pub const MAX_PORTFOLIO_ASSETS: usize = 8;
+
+/// A mint held by a portfolio and the share of the portfolio's value it should make up.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct PortfolioAsset {
+    pub mint: Pubkey,
+    pub target_bps: u16,
+}
+
+/// Treasury held in the portfolio PDA's own token accounts. Keepers rebalance it towards the
+/// target weights with `rebalance_portfolio`, valued at the oracle prices configured for each
+/// mint.
+#[account]
+#[derive(InitSpace)]
+pub struct Portfolio {
+    /// Sets the targets and withdraws from the portfolio
+    pub authority: Pubkey,
+    pub id: u64,
+    /// Target weights, adding up to 10000 bps
+    #[max_len(MAX_PORTFOLIO_ASSETS)]
+    pub assets: Vec<PortfolioAsset>,
+    /// Most value a single rebalance may lose against the oracle rate, on the swap and on the
+    /// portfolio as a whole
+    pub max_slippage_bps: u16,
+    pub bump: u8,
+}
+
+impl Portfolio {
+    pub fn validate_targets(assets: &[PortfolioAsset], max_slippage_bps: u16) -> Result<()> {
+        require_gte!(MAX_PORTFOLIO_ASSETS, assets.len(), SwapError::ListTooLong);
+        require_gte!(
+            BPS_DENOMINATOR,
+            u64::from(max_slippage_bps),
+            SwapError::InvalidBps
+        );
+        let total_bps: u64 = assets.iter().map(|asset| u64::from(asset.target_bps)).sum();
+        require_eq!(
+            total_bps,
+            BPS_DENOMINATOR,
+            SwapError::InvalidPortfolioTargets
+        );
+        for (i, asset) in assets.iter().enumerate() {
+            require!(
+                assets[..i].iter().all(|other| other.mint != asset.mint),
+                SwapError::InvalidPortfolioTargets
+            );
+        }
+        Ok(())
+    }
+
+    pub fn holds(&self, mint: &Pubkey) -> bool {
+        self.assets.iter().any(|asset| asset.mint == *mint)
+    }
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+
+/// Output paid out of one vault in the current window, either for a whole mint or for one
+/// caller and mint depending on how the PDA is seeded. Windows are fixed blocks of the
+/// config's `rate_limit_window_slots` slots and the volume resets when a new one starts.
+#[account]
+#[derive(InitSpace)]
+pub struct RateLimit {
+    /// Index of the window `volume` was counted in, i.e. slot / window length
+    pub window: u64,
+    pub volume: u64,
+    /// Overrides the config's default cap when set by the config authority
+    pub cap: Option<u64>,
+    pub bump: u8,
+}
+
+impl RateLimit {
+    /// Adds `amount` to the current window's volume and fails if it goes over the cap. A zero
+    /// window length turns rate limiting off.
+    pub fn record(
+        &mut self,
+        slot: u64,
+        window_slots: u64,
+        default_cap: u64,
+        amount: u64,
+    ) -> Result<()> {
+        if window_slots == 0 {
+            return Ok(());
+        }
+        let window = slot / window_slots;
+        if window != self.window {
+            self.window = window;
+            self.volume = 0;
+        }
+        self.volume = self
+            .volume
+            .checked_add(amount)
+            .ok_or(SwapError::MathOverflow)?;
+        require_gte!(
+            self.cap.unwrap_or(default_cap),
+            self.volume,
+            SwapError::RateLimitExceeded
+        );
+        Ok(())
+    }
+}
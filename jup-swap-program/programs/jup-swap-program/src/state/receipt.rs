@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// How long a receipt has to be kept before its payer can close it and reclaim the rent. This is
+/// also how long its id is guaranteed to stay taken.
+pub const RECEIPT_RETENTION_PERIOD: i64 = 90 * 24 * 60 * 60;
+
+/// Proof that `payer`'s swap paid `recipient`, created by `swap` when asked to. It's keyed by
+/// a payer chosen id, so retrying a payment with the same id fails instead of paying twice, for
+/// as long as the receipt exists. Once `close_receipt` removes it the id is free again, so
+/// payers retrying past `RECEIPT_RETENTION_PERIOD` must not close receipts they may still retry.
+#[account]
+#[derive(InitSpace)]
+pub struct Receipt {
+    pub payer: Pubkey,
+    pub id: u64,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub amount_paid: u64,
+    pub recipient: Pubkey,
+    pub slot: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
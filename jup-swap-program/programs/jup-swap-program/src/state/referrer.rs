@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+/// Referral earnings of `referrer` in `mint` on `integrator`'s swaps. The integrator's authority
+/// approves the referrer by creating it with `approve_referrer`, then `swap` pays the referral
+/// share of the fee into this account's ATA and the referrer claims it with `claim_referral`.
+#[account]
+#[derive(InitSpace)]
+pub struct Referrer {
+    pub integrator: Pubkey,
+    pub referrer: Pubkey,
+    pub mint: Pubkey,
+    /// Earned and not claimed yet, the escrow's balance
+    pub unclaimed: u64,
+    /// Earned since the account was created, claimed or not
+    pub total_earned: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+/// Prices of the Pyth feed `feed_id` a trigger order fires at, `price * 10^exponent` in the
+/// feed's quote currency. At least one of them is set.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct Trigger {
+    pub feed_id: [u8; 32],
+    /// Fires when the price falls to or below this
+    pub stop_loss_price: Option<i64>,
+    /// Fires when the price rises to or above this
+    pub take_profit_price: Option<i64>,
+    pub exponent: i32,
+}
+
+/// Stop-loss / take-profit order escrowing `amount_in` of `input_mint` in the order's own token
+/// account. Keepers swap it into `output_mint` with `trigger_order` once the oracle crosses one
+/// of the trigger prices, as long as the route pays out at least `min_amount_out`.
+#[account]
+#[derive(InitSpace)]
+pub struct TriggerOrder {
+    pub owner: Pubkey,
+    pub id: u64,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub trigger: Trigger,
+    pub bump: u8,
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+
+/// Price protection applied to the fills of a TWAP order.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct TwapLimits {
+    /// Minimum output for a regular slice, pro-rated for the last one
+    pub min_out_per_slice: u64,
+    /// Expected output for a regular slice, the fixed reference price for the fills
+    pub reference_out_per_slice: u64,
+    /// How far below the reference price the average price across fills may go
+    pub max_deviation_bps: u16,
+}
+
+/// Large order split into `num_slices` child swaps, each at least `min_slot_gap` slots after
+/// the previous one. Fills are permissionless, so the average price of the fills is checked
+/// against the reference price the owner set at creation rather than against earlier fills a
+/// keeper could have steered.
+#[account]
+#[derive(InitSpace)]
+pub struct TwapOrder {
+    pub owner: Pubkey,
+    pub id: u64,
+    pub recipient: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub total_input: u64,
+    pub num_slices: u32,
+    pub slices_filled: u32,
+    pub min_slot_gap: u64,
+    pub last_fill_slot: u64,
+    pub filled_input: u64,
+    pub filled_output: u64,
+    pub limits: TwapLimits,
+    pub bump: u8,
+}
+
+impl TwapOrder {
+    /// Input for the next slice, the last one also takes the rounding remainder.
+    pub fn next_slice_amount(&self) -> Result<u64> {
+        require_gt!(self.num_slices, self.slices_filled, SwapError::OrderFilled);
+        if self.slices_filled + 1 == self.num_slices {
+            self.total_input
+                .checked_sub(self.filled_input)
+                .ok_or(SwapError::MathOverflow.into())
+        } else {
+            Ok(self.total_input / u64::from(self.num_slices))
+        }
+    }
+}
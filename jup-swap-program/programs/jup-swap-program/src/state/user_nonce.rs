@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+/// Next intent nonce a user is allowed to sign, bumped every time one of their intents executes.
+#[account]
+#[derive(InitSpace)]
+pub struct UserNonce {
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+
+/// Swap output locked for `recipient`, created by `swap` in vesting mode. Nothing unlocks
+/// before `cliff`, after that `total` unlocks linearly from `start` until `end` and the
+/// recipient claims it with `claim_vested`. The output sits in a program owned token account
+/// at `[VESTING_ESCROW_SEED, recipient, id]` so nobody can create it ahead of the swap. The
+/// claim that empties it closes both accounts, refunding their rent to the recipient.
+#[account]
+#[derive(InitSpace)]
+pub struct Vesting {
+    pub recipient: Pubkey,
+    pub id: u64,
+    pub mint: Pubkey,
+    pub total: u64,
+    pub claimed: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+    pub bump: u8,
+}
+
+impl Vesting {
+    /// How much of `total` has unlocked at `now`, claimed or not.
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        if now < self.cliff {
+            return Ok(0);
+        }
+        if now >= self.end {
+            return Ok(self.total);
+        }
+        let elapsed = u128::try_from(now - self.start).map_err(|_| SwapError::MathOverflow)?;
+        let duration =
+            u128::try_from(self.end - self.start).map_err(|_| SwapError::MathOverflow)?;
+        let vested = u128::from(self.total)
+            .checked_mul(elapsed)
+            .ok_or(SwapError::MathOverflow)?
+            / duration;
+        u64::try_from(vested).map_err(|_| SwapError::MathOverflow.into())
+    }
+}
@@ -0,0 +1,450 @@
+//! Typed helpers for calling `swap` from another Anchor program. Enable the `cpi` feature and
+//! build the accounts with [`SwapAccountsBuilder`], pass the aggregator's route accounts as the
+//! context's remaining accounts and call [`swap`]:
+//!
+//! ```ignore
+//! let accounts = SwapAccountsBuilder::new()
+//!     .payer(payer)
+//!     .mints(input_mint, input_mint_token_program, output_mint, output_mint_token_program)
+//!     .vault(vault, vault_input_token_account, vault_output_token_account)
+//!     .recipient(recipient, recipient_token_account)
+//!     .programs(associated_token_program, aggregator_program, system_program)
+//!     .config(config)
+//!     .rate_limits(mint_rate_limit, caller_rate_limit)
+//!     .mint_stats(mint_stats)
+//!     .surplus_destination(surplus_destination)
+//!     .build()?;
+//! let cpi_ctx = CpiContext::new(jup_swap_program, accounts)
+//!     .with_remaining_accounts(route_accounts);
+//! let result = swap_cpi::swap(cpi_ctx, SwapArgs::new(route_data, amount))?;
+//! ```
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::cpi::accounts::Swap;
+use crate::{
+    SwapCallback, SwapResult, VestingSchedule, CALLER_RATE_LIMIT_SEED, CONFIG_SEED,
+    INTEGRATOR_SEED, MINT_RATE_LIMIT_SEED, MINT_STATS_SEED, RECEIPT_SEED, REFERRER_SEED,
+    VAULT_SEED, VESTING_ESCROW_SEED, VESTING_SEED,
+};
+
+/// Arguments of the `swap` instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapArgs {
+    /// Instruction data for an allowlisted aggregator, e.g. Jupiter's as returned by the
+    /// swap-instructions API
+    pub route_data: Vec<u8>,
+    /// Output paid to the recipient
+    pub amount: u64,
+    pub callback: Option<SwapCallback>,
+    /// Creates a receipt with this id, see [`SwapAccountsBuilder::receipt`]
+    pub receipt_id: Option<u64>,
+    /// Vests the payout instead of paying it out, see [`SwapAccountsBuilder::vesting`]
+    pub vesting_schedule: Option<VestingSchedule>,
+    /// Logged with the memo program right before the payout, see
+    /// [`SwapAccountsBuilder::memo_program`]
+    pub memo: Option<String>,
+}
+
+impl SwapArgs {
+    pub fn new(route_data: Vec<u8>, amount: u64) -> Self {
+        Self {
+            route_data,
+            amount,
+            callback: None,
+            receipt_id: None,
+            vesting_schedule: None,
+            memo: None,
+        }
+    }
+
+    /// Runs `callback` after the payout, its accounts go last in the remaining accounts.
+    pub fn with_callback(mut self, callback: SwapCallback) -> Self {
+        self.callback = Some(callback);
+        self
+    }
+
+    pub fn with_receipt(mut self, receipt_id: u64) -> Self {
+        self.receipt_id = Some(receipt_id);
+        self
+    }
+
+    pub fn with_vesting(mut self, vesting_schedule: VestingSchedule) -> Self {
+        self.vesting_schedule = Some(vesting_schedule);
+        self
+    }
+
+    pub fn with_memo(mut self, memo: String) -> Self {
+        self.memo = Some(memo);
+        self
+    }
+}
+
+/// Builds the accounts of `swap`, optional accounts are left out unless they're set.
+#[derive(Default)]
+pub struct SwapAccountsBuilder<'info> {
+    payer: Option<AccountInfo<'info>>,
+    input_mint: Option<AccountInfo<'info>>,
+    input_mint_token_program: Option<AccountInfo<'info>>,
+    output_mint: Option<AccountInfo<'info>>,
+    output_mint_token_program: Option<AccountInfo<'info>>,
+    vault: Option<AccountInfo<'info>>,
+    vault_input_token_account: Option<AccountInfo<'info>>,
+    vault_output_token_account: Option<AccountInfo<'info>>,
+    recipient_token_account: Option<AccountInfo<'info>>,
+    recipient: Option<AccountInfo<'info>>,
+    associated_token_program: Option<AccountInfo<'info>>,
+    aggregator_program: Option<AccountInfo<'info>>,
+    config: Option<AccountInfo<'info>>,
+    mint_rate_limit: Option<AccountInfo<'info>>,
+    caller_rate_limit: Option<AccountInfo<'info>>,
+    mint_stats: Option<AccountInfo<'info>>,
+    callback_program: Option<AccountInfo<'info>>,
+    receipt: Option<AccountInfo<'info>>,
+    vesting: Option<AccountInfo<'info>>,
+    vesting_escrow: Option<AccountInfo<'info>>,
+    system_program: Option<AccountInfo<'info>>,
+    input_price_update: Option<AccountInfo<'info>>,
+    output_price_update: Option<AccountInfo<'info>>,
+    integrator: Option<AccountInfo<'info>>,
+    fee_treasury_token_account: Option<AccountInfo<'info>>,
+    referrer: Option<AccountInfo<'info>>,
+    referrer_account: Option<AccountInfo<'info>>,
+    referrer_escrow: Option<AccountInfo<'info>>,
+    memo_program: Option<AccountInfo<'info>>,
+    surplus_destination: Option<AccountInfo<'info>>,
+}
+
+impl<'info> SwapAccountsBuilder<'info> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signs the swap and pays for the receipt, if one is created.
+    pub fn payer(mut self, payer: AccountInfo<'info>) -> Self {
+        self.payer = Some(payer);
+        self
+    }
+
+    pub fn mints(
+        mut self,
+        input_mint: AccountInfo<'info>,
+        input_mint_token_program: AccountInfo<'info>,
+        output_mint: AccountInfo<'info>,
+        output_mint_token_program: AccountInfo<'info>,
+    ) -> Self {
+        self.input_mint = Some(input_mint);
+        self.input_mint_token_program = Some(input_mint_token_program);
+        self.output_mint = Some(output_mint);
+        self.output_mint_token_program = Some(output_mint_token_program);
+        self
+    }
+
+    /// The vault PDA and its ATAs of the input and output mints, see [`vault_address`] and
+    /// [`vault_token_account_address`].
+    pub fn vault(
+        mut self,
+        vault: AccountInfo<'info>,
+        vault_input_token_account: AccountInfo<'info>,
+        vault_output_token_account: AccountInfo<'info>,
+    ) -> Self {
+        self.vault = Some(vault);
+        self.vault_input_token_account = Some(vault_input_token_account);
+        self.vault_output_token_account = Some(vault_output_token_account);
+        self
+    }
+
+    /// The recipient and its ATA of the output mint.
+    pub fn recipient(
+        mut self,
+        recipient: AccountInfo<'info>,
+        recipient_token_account: AccountInfo<'info>,
+    ) -> Self {
+        self.recipient = Some(recipient);
+        self.recipient_token_account = Some(recipient_token_account);
+        self
+    }
+
+    pub fn programs(
+        mut self,
+        associated_token_program: AccountInfo<'info>,
+        aggregator_program: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+    ) -> Self {
+        self.associated_token_program = Some(associated_token_program);
+        self.aggregator_program = Some(aggregator_program);
+        self.system_program = Some(system_program);
+        self
+    }
+
+    /// The config PDA, see [`config_address`].
+    pub fn config(mut self, config: AccountInfo<'info>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// The output mint's and the payer's rate limit PDAs, see [`mint_rate_limit_address`] and
+    /// [`caller_rate_limit_address`].
+    pub fn rate_limits(
+        mut self,
+        mint_rate_limit: AccountInfo<'info>,
+        caller_rate_limit: AccountInfo<'info>,
+    ) -> Self {
+        self.mint_rate_limit = Some(mint_rate_limit);
+        self.caller_rate_limit = Some(caller_rate_limit);
+        self
+    }
+
+    /// The mint pair's stats PDA, see [`mint_stats_address`].
+    pub fn mint_stats(mut self, mint_stats: AccountInfo<'info>) -> Self {
+        self.mint_stats = Some(mint_stats);
+        self
+    }
+
+    /// Only needed when [`SwapArgs::callback`] is set.
+    pub fn callback_program(mut self, callback_program: AccountInfo<'info>) -> Self {
+        self.callback_program = Some(callback_program);
+        self
+    }
+
+    /// Only needed when [`SwapArgs::receipt_id`] is set, see [`receipt_address`].
+    pub fn receipt(mut self, receipt: AccountInfo<'info>) -> Self {
+        self.receipt = Some(receipt);
+        self
+    }
+
+    /// Vesting mode, replaces the recipient token account set by [`Self::recipient`]. See
+    /// [`vesting_address`] and [`vesting_escrow_address`].
+    pub fn vesting(
+        mut self,
+        recipient: AccountInfo<'info>,
+        vesting: AccountInfo<'info>,
+        vesting_escrow: AccountInfo<'info>,
+    ) -> Self {
+        self.recipient = Some(recipient);
+        self.recipient_token_account = None;
+        self.vesting = Some(vesting);
+        self.vesting_escrow = Some(vesting_escrow);
+        self
+    }
+
+    /// Pyth `PriceUpdateV2` accounts of both mints, to reject the swap if its rate is too far
+    /// from theirs.
+    pub fn price_updates(
+        mut self,
+        input_price_update: AccountInfo<'info>,
+        output_price_update: AccountInfo<'info>,
+    ) -> Self {
+        self.input_price_update = Some(input_price_update);
+        self.output_price_update = Some(output_price_update);
+        self
+    }
+
+    /// Swaps for an integrator, out of its vault (see [`integrator_vault_address`]) and paying
+    /// its fee into `fee_treasury_token_account`, the fee treasury's account of the output mint.
+    pub fn integrator(
+        mut self,
+        integrator: AccountInfo<'info>,
+        fee_treasury_token_account: AccountInfo<'info>,
+    ) -> Self {
+        self.integrator = Some(integrator);
+        self.fee_treasury_token_account = Some(fee_treasury_token_account);
+        self
+    }
+
+    /// Pays `referrer` the integrator's referral share of the fee, into the escrow of its
+    /// [`referrer_address`] PDA, see [`referrer_escrow_address`]. Only pays with an integrator,
+    /// and only referrers the integrator approved.
+    pub fn referrer(
+        mut self,
+        referrer: AccountInfo<'info>,
+        referrer_account: AccountInfo<'info>,
+        referrer_escrow: AccountInfo<'info>,
+    ) -> Self {
+        self.referrer = Some(referrer);
+        self.referrer_account = Some(referrer_account);
+        self.referrer_escrow = Some(referrer_escrow);
+        self
+    }
+
+    /// Only needed when [`SwapArgs::memo`] is set.
+    pub fn memo_program(mut self, memo_program: AccountInfo<'info>) -> Self {
+        self.memo_program = Some(memo_program);
+        self
+    }
+
+    /// Output token account of the payer, or of the config's surplus treasury when it has one,
+    /// receiving what the route delivered beyond the payout and the fee.
+    pub fn surplus_destination(mut self, surplus_destination: AccountInfo<'info>) -> Self {
+        self.surplus_destination = Some(surplus_destination);
+        self
+    }
+
+    /// Fails with `AccountNotEnoughKeys` if a required account hasn't been set.
+    pub fn build(self) -> Result<Swap<'info>> {
+        let required =
+            |account: Option<AccountInfo<'info>>| account.ok_or(ErrorCode::AccountNotEnoughKeys);
+        Ok(Swap {
+            payer: required(self.payer)?,
+            input_mint: required(self.input_mint)?,
+            input_mint_token_program: required(self.input_mint_token_program)?,
+            output_mint: required(self.output_mint)?,
+            output_mint_token_program: required(self.output_mint_token_program)?,
+            vault: required(self.vault)?,
+            vault_input_token_account: required(self.vault_input_token_account)?,
+            vault_output_token_account: required(self.vault_output_token_account)?,
+            recipient_token_account: self.recipient_token_account,
+            recipient: required(self.recipient)?,
+            associated_token_program: required(self.associated_token_program)?,
+            aggregator_program: required(self.aggregator_program)?,
+            config: required(self.config)?,
+            mint_rate_limit: required(self.mint_rate_limit)?,
+            caller_rate_limit: required(self.caller_rate_limit)?,
+            mint_stats: required(self.mint_stats)?,
+            callback_program: self.callback_program,
+            receipt: self.receipt,
+            vesting: self.vesting,
+            vesting_escrow: self.vesting_escrow,
+            system_program: required(self.system_program)?,
+            input_price_update: self.input_price_update,
+            output_price_update: self.output_price_update,
+            integrator: self.integrator,
+            fee_treasury_token_account: self.fee_treasury_token_account,
+            referrer: self.referrer,
+            referrer_account: self.referrer_account,
+            referrer_escrow: self.referrer_escrow,
+            memo_program: self.memo_program,
+            surplus_destination: required(self.surplus_destination)?,
+        })
+    }
+}
+
+/// Calls `swap` and decodes the [`SwapResult`] it returns.
+pub fn swap<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, Swap<'info>>,
+    args: SwapArgs,
+) -> Result<SwapResult> {
+    crate::cpi::swap(
+        ctx,
+        args.route_data,
+        args.amount,
+        args.callback,
+        args.receipt_id,
+        args.vesting_schedule,
+        args.memo,
+    )
+    .map(|ret| ret.get())
+}
+
+pub fn vault_address() -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_SEED], &crate::ID).0
+}
+
+pub fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_SEED], &crate::ID).0
+}
+
+/// The vault's ATA of `mint`, owned by `token_program`.
+pub fn vault_token_account_address(mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(&vault_address(), mint, token_program)
+}
+
+/// The receipt `swap` creates for `payer` with `receipt_id`.
+pub fn receipt_address(payer: &Pubkey, receipt_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[RECEIPT_SEED, payer.as_ref(), &receipt_id.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// The vesting account `swap` creates for `recipient` in vesting mode.
+pub fn vesting_address(recipient: &Pubkey, vesting_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[VESTING_SEED, recipient.as_ref(), &vesting_id.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// The token account holding the vesting account's locked output.
+pub fn vesting_escrow_address(recipient: &Pubkey, vesting_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            VESTING_ESCROW_SEED,
+            recipient.as_ref(),
+            &vesting_id.to_le_bytes(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Rate limit of the payouts in `mint` out of `vault`.
+pub fn mint_rate_limit_address(vault: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MINT_RATE_LIMIT_SEED, vault.as_ref(), mint.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Rate limit of the payouts in `mint` out of `vault` on swaps paid for by `caller`.
+pub fn caller_rate_limit_address(vault: &Pubkey, caller: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            CALLER_RATE_LIMIT_SEED,
+            vault.as_ref(),
+            caller.as_ref(),
+            mint.as_ref(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn integrator_address(id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[INTEGRATOR_SEED, &id.to_le_bytes()], &crate::ID).0
+}
+
+/// The vault swaps for `integrator` run out of.
+pub fn integrator_vault_address(integrator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_SEED, integrator.as_ref()], &crate::ID).0
+}
+
+/// Referral earnings of `referrer` in `mint` on `integrator`'s swaps.
+pub fn referrer_address(integrator: &Pubkey, referrer: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            REFERRER_SEED,
+            integrator.as_ref(),
+            referrer.as_ref(),
+            mint.as_ref(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+/// The referrer account's ATA holding its unclaimed earnings.
+pub fn referrer_escrow_address(
+    integrator: &Pubkey,
+    referrer: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    get_associated_token_address_with_program_id(
+        &referrer_address(integrator, referrer, mint),
+        mint,
+        token_program,
+    )
+}
+
+/// Swap totals from `input_mint` to `output_mint`.
+pub fn mint_stats_address(input_mint: &Pubkey, output_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MINT_STATS_SEED, input_mint.as_ref(), output_mint.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
@@ -0,0 +1,181 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::Instruction,
+        program::{invoke, invoke_signed},
+    },
+};
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::error::SwapError;
+use crate::state::Config;
+
+/// Invokes an allowlisted aggregator with the route accounts passed in as remaining accounts,
+/// signing for `signer` wherever it shows up in the route.
+pub fn invoke_aggregator<'info>(
+    config: &Config,
+    aggregator_program: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+    signer: &Pubkey,
+    data: Vec<u8>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    config.check_route(aggregator_program, &data)?;
+
+    //Convert the remaining accounts gotten from the jupiter swap api to Account Meta Objects
+    let accounts: Vec<AccountMeta> = remaining_accounts
+        .iter()
+        .map(|acc| AccountMeta {
+            pubkey: *acc.key,
+            is_signer: acc.key == signer,
+            is_writable: acc.is_writable,
+        })
+        .collect();
+
+    //Convert the remaining accounts to account infos
+    let accounts_infos: Vec<AccountInfo> = remaining_accounts.to_vec();
+
+    invoke_signed(
+        &Instruction {
+            program_id: *aggregator_program,
+            accounts,
+            data,
+        },
+        &accounts_infos,
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+/// Runs the aggregator route for `signer` and returns how much `output_token_account` received
+/// from it, so whatever balance the account held before doesn't count towards the swap.
+pub fn swap_and_measure(
+    config: &Config,
+    aggregator_program: &Pubkey,
+    remaining_accounts: &[AccountInfo<'_>],
+    signer: &Pubkey,
+    output_token_account: &mut InterfaceAccount<'_, TokenAccount>,
+    data: Vec<u8>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    let output_before = output_token_account.amount;
+    invoke_aggregator(
+        config,
+        aggregator_program,
+        remaining_accounts,
+        signer,
+        data,
+        signer_seeds,
+    )?;
+    output_token_account.reload()?;
+    output_token_account
+        .amount
+        .checked_sub(output_before)
+        .ok_or(SwapError::MathOverflow.into())
+}
+
+/// Runs the aggregator route like [`swap_and_measure`] and also requires it to spend exactly
+/// `amount_in` out of `input_token_account`. Input the route leaves behind would otherwise sit in
+/// the shared vault, where any later route could take it.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_exact_in(
+    config: &Config,
+    aggregator_program: &Pubkey,
+    remaining_accounts: &[AccountInfo<'_>],
+    signer: &Pubkey,
+    input_token_account: &mut InterfaceAccount<'_, TokenAccount>,
+    output_token_account: &mut InterfaceAccount<'_, TokenAccount>,
+    amount_in: u64,
+    data: Vec<u8>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    input_token_account.reload()?;
+    let input_before = input_token_account.amount;
+    let amount_out = swap_and_measure(
+        config,
+        aggregator_program,
+        remaining_accounts,
+        signer,
+        output_token_account,
+        data,
+        signer_seeds,
+    )?;
+    input_token_account.reload()?;
+    let input_spent = input_before
+        .checked_sub(input_token_account.amount)
+        .ok_or(SwapError::RouteInputMismatch)?;
+    require_eq!(input_spent, amount_in, SwapError::RouteInputMismatch);
+
+    Ok(amount_out)
+}
+
+/// Logs `memo` with the SPL Memo program. Token-2022 accounts requiring memos accept a transfer
+/// made right after it.
+pub fn invoke_memo(memo_program: &AccountInfo<'_>, memo: &str) -> Result<()> {
+    invoke(
+        &Instruction {
+            program_id: *memo_program.key,
+            accounts: vec![],
+            data: memo.as_bytes().to_vec(),
+        },
+        std::slice::from_ref(memo_program),
+    )?;
+
+    Ok(())
+}
+
+/// `transfer_checked` out of a token account owned by one of the program's PDAs.
+pub fn transfer_signed<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        mint: mint.to_account_info(),
+        from,
+        to,
+        authority,
+    };
+    let cpi_context =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+/// `transfer_checked` out of a token account whose owner signs the transaction.
+pub fn transfer_tokens<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    transfer_signed(token_program, mint, from, to, authority, amount, &[])
+}
+
+/// Closes an empty token account owned by one of the program's PDAs, sending its rent to
+/// `destination`.
+pub fn close_token_account_signed<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    account: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = CloseAccount {
+        account,
+        destination,
+        authority,
+    };
+    let cpi_context =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    close_account(cpi_context)
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use jup_swap_program::program::JupSwapProgram;
+use jup_swap_program::swap_cpi::{self, SwapAccountsBuilder, SwapArgs};
+
+declare_id!("SuCvJuapuxYGUbbYsdeLCaQkAm6xSmHXuYTqrMYD5SL");
+
+//  - example of another program swapping through jup_swap_program over CPI
+//  - the Jupiter route accounts are forwarded as remaining accounts
+
+#[program]
+pub mod swap_caller {
+    use super::*;
+
+    pub fn swap_exact<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapExact<'info>>,
+        route_data: Vec<u8>,
+        amount: u64,
+    ) -> Result<()> {
+        let accounts = SwapAccountsBuilder::new()
+            .payer(ctx.accounts.payer.to_account_info())
+            .mints(
+                ctx.accounts.input_mint.to_account_info(),
+                ctx.accounts.input_mint_token_program.to_account_info(),
+                ctx.accounts.output_mint.to_account_info(),
+                ctx.accounts.output_mint_token_program.to_account_info(),
+            )
+            .vault(
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.vault_input_token_account.to_account_info(),
+                ctx.accounts.vault_output_token_account.to_account_info(),
+            )
+            .recipient(
+                ctx.accounts.recipient.to_account_info(),
+                ctx.accounts.recipient_token_account.to_account_info(),
+            )
+            .programs(
+                ctx.accounts.associated_token_program.to_account_info(),
+                ctx.accounts.aggregator_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            )
+            .config(ctx.accounts.config.to_account_info())
+            .rate_limits(
+                ctx.accounts.mint_rate_limit.to_account_info(),
+                ctx.accounts.caller_rate_limit.to_account_info(),
+            )
+            .mint_stats(ctx.accounts.mint_stats.to_account_info())
+            .surplus_destination(ctx.accounts.surplus_destination.to_account_info())
+            .build()?;
+        let cpi_ctx = CpiContext::new(ctx.accounts.jup_swap_program.to_account_info(), accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        let result = swap_cpi::swap(cpi_ctx, SwapArgs::new(route_data, amount))?;
+        require_eq!(result.amount_paid, amount, CallerError::UnexpectedPayout);
+
+        msg!(
+            "Swapped {} in for {} out, {} paid",
+            result.amount_in,
+            result.amount_out,
+            result.amount_paid
+        );
+
+        Ok(())
+    }
+}
+
+#[error_code]
+pub enum CallerError {
+    #[msg("Swap paid out a different amount than requested")]
+    UnexpectedPayout,
+}
+
+// Everything below is validated by jup_swap_program itself
+#[derive(Accounts)]
+pub struct SwapExact<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: validated by jup_swap_program
+    pub input_mint: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    pub input_mint_token_program: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    pub output_mint: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    pub output_mint_token_program: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    #[account(mut)]
+    pub vault_input_token_account: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    #[account(mut)]
+    pub vault_output_token_account: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    #[account(mut)]
+    pub recipient_token_account: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    pub recipient: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    pub associated_token_program: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    pub aggregator_program: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    #[account(mut)]
+    pub mint_rate_limit: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    #[account(mut)]
+    pub caller_rate_limit: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    #[account(mut)]
+    pub mint_stats: UncheckedAccount<'info>,
+    /// CHECK: validated by jup_swap_program
+    #[account(mut)]
+    pub surplus_destination: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub jup_swap_program: Program<'info, JupSwapProgram>,
+}